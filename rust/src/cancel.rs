@@ -0,0 +1,47 @@
+//! Cooperative cancellation. The runner hands each part a token and raises it when the part runs
+//! past its time limit; long-running solvers call [`cancelled`] in their loops and return early,
+//! with whatever answer, when it says so. Solvers that never check are abandoned instead.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+pub type Token = Arc<AtomicBool>;
+
+thread_local! {
+    static TOKEN: RefCell<Option<Token>> = const { RefCell::new(None) };
+}
+
+/// Whether the work running on this thread should stop.
+pub fn cancelled() -> bool {
+    TOKEN.with(|token| token.borrow().as_ref().is_some_and(|token| token.load(Ordering::Relaxed)))
+}
+
+/// The token of the work running on this thread, to hand over to threads it starts.
+pub fn token() -> Option<Token> {
+    TOKEN.with(|token| token.borrow().clone())
+}
+
+/// Puts back the token it holds when dropped, so that it is restored even if the work panics.
+struct Restore(Option<Token>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        TOKEN.with(|current| current.replace(previous));
+    }
+}
+
+/// Runs `f` with `token` as the token of this thread.
+pub fn with_token<R>(token: Option<Token>, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(TOKEN.with(|current| current.replace(token)));
+    f()
+}
+
+pub fn cancel(token: &Token) {
+    token.store(true, Ordering::Relaxed);
+}
+
+pub fn is_cancelled(token: &Token) -> bool {
+    token.load(Ordering::Relaxed)
+}
@@ -1,4 +1,4 @@
-use crate::tools::{read_example, read_input};
+use crate::parsing::ints;
 use crate::DaySolution;
 use std::collections::HashMap;
 
@@ -13,19 +13,13 @@ impl DaySolution<(Vec<i32>, Vec<i32>), i32> for Day01 {
         1
     }
 
-    fn parse_input(&mut self, example: bool) -> (Vec<i32>, Vec<i32>) {
+    fn parse_input(&mut self, raw_data: &str) -> (Vec<i32>, Vec<i32>) {
         let mut res = (vec![], vec![]);
-        let raw_data = if example {
-            read_example(self.day())
-        } else {
-            read_input(self.day())
-        };
 
         for line in raw_data.lines() {
-            let parts = line.split_whitespace().collect::<Vec<&str>>();
-            if parts.len() == 2 {
-                res.0.push(parts[0].parse().unwrap());
-                res.1.push(parts[1].parse().unwrap());
+            if let [left, right] = ints(line).unwrap()[..] {
+                res.0.push(left);
+                res.1.push(right);
             }
         }
         res
@@ -1,35 +1,140 @@
-use crate::tools::{read_example, read_input};
-use crate::DaySolution;
+use crate::{parallel, DaySolution, Part};
+use std::ops::RangeInclusive;
 
-pub struct Day02 {}
+/// A report is safe when its levels all increase or all decrease, each step by an amount in
+/// `steps`, possibly after dropping up to `max_removals` levels.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SafetyRules {
+    pub steps: RangeInclusive<i32>,
+    pub max_removals: usize,
+}
+
+impl SafetyRules {
+    pub fn new(max_removals: usize) -> Self {
+        SafetyRules {
+            steps: 1..=3,
+            max_removals,
+        }
+    }
+
+    /// The fewest levels to drop for the report to be safe, by index, or `None` when more than
+    /// `max_removals` would be needed.
+    ///
+    /// `best[i]` is the fewest removals among the levels before `i` when `i` is kept. Keeping `i`
+    /// right after `p` drops everything between them, so only the `max_removals + 1` levels
+    /// before `i` can precede it: O(n * k) overall, linear for a fixed `k`.
+    pub fn make_safe(&self, report: &[i32]) -> Option<Vec<usize>> {
+        [1, -1].iter()
+            .filter_map(|direction| self.make_safe_towards(report, *direction))
+            .min_by_key(|removed| removed.len())
+    }
+
+    fn make_safe_towards(&self, report: &[i32], direction: i32) -> Option<Vec<usize>> {
+        let n = report.len();
+        let k = self.max_removals;
+        if n <= 1 {
+            return Some(vec![]);
+        }
+
+        let mut best = vec![usize::MAX; n];
+        let mut previous = vec![None; n];
+        for i in 0..n {
+            if i <= k {
+                best[i] = i;
+            }
+            for p in i.saturating_sub(k + 1)..i {
+                let removals = best[p].saturating_add(i - p - 1);
+                if removals < best[i] && self.steps.contains(&((report[i] - report[p]) * direction)) {
+                    best[i] = removals;
+                    previous[i] = Some(p);
+                }
+            }
+        }
+
+        let last = (n.saturating_sub(k + 1)..n)
+            .filter(|i| best[*i] != usize::MAX)
+            .min_by_key(|i| best[*i] + (n - 1 - i))
+            .filter(|i| best[*i] + (n - 1 - i) <= k)?;
+
+        let mut kept = vec![false; n];
+        let mut current = Some(last);
+        while let Some(i) = current {
+            kept[i] = true;
+            current = previous[i];
+        }
+        Some((0..n).filter(|i| !kept[*i]).collect())
+    }
+
+    /// Tries every way to drop up to `max_removals` levels, as a reference for [`Self::make_safe`].
+    pub fn is_safe_by_brute_force(&self, report: &[i32]) -> bool {
+        if self.is_safe(report) {
+            return true;
+        }
+        self.max_removals > 0 && (0..report.len()).any(|i| {
+            let mut new_report = report.to_vec();
+            new_report.remove(i);
+            SafetyRules { steps: self.steps.clone(), max_removals: self.max_removals - 1 }.is_safe_by_brute_force(&new_report)
+        })
+    }
+
+    /// Whether the report is safe as it is, without dropping any level. Like the DP, it tries
+    /// each direction in turn, so that a step of 0 allowed by `steps` goes either way.
+    pub fn is_safe(&self, report: &[i32]) -> bool {
+        [1, -1].iter().any(|direction| {
+            report.windows(2).all(|pair| self.steps.contains(&((pair[1] - pair[0]) * direction)))
+        })
+    }
+}
+
+/// How a report is checked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Check {
+    /// [`SafetyRules::make_safe`].
+    Dp,
+    /// [`SafetyRules::is_safe_by_brute_force`].
+    BruteForce,
+}
+
+impl Check {
+    const fn name(self) -> &'static str {
+        match self {
+            Check::Dp => "dp",
+            Check::BruteForce => "brute-force",
+        }
+    }
+}
+
+const CHECKS: [Check; 2] = [Check::Dp, Check::BruteForce];
+const STRATEGIES: [&str; 2] = [CHECKS[0].name(), CHECKS[1].name()];
+
+pub struct Day02 {
+    checks: [Check; 2],
+}
 
 impl Day02 {
-    fn is_safe(&self, report: &Vec<i32>) -> bool {
-        let increase = report[1] - report[0] >= 0;
-        report.windows(2)
-            .all(|pair| {
-                let diff = pair[1] - pair[0];
-                let pair_increase = diff >= 0;
-                increase == pair_increase && diff.abs() <= 3 && diff.abs() >= 1
-            })
+    fn count_safe(&self, reports: &[Vec<i32>], part: Part, max_removals: usize) -> i32 {
+        let rules = SafetyRules::new(max_removals);
+        let check = self.checks[part.index()];
+        parallel::map(reports, |report| match check {
+            Check::Dp => rules.make_safe(report).is_some(),
+            Check::BruteForce => rules.is_safe_by_brute_force(report),
+        })
+            .into_iter()
+            .filter(|safe| *safe)
+            .count() as i32
     }
 }
 
 impl DaySolution<Vec<Vec<i32>>, i32> for Day02 {
     fn new() -> Self {
-        Day02 {}
+        Day02 { checks: [Check::Dp; 2] }
     }
 
     fn day(&self) -> u16 {
         2_u16
     }
 
-    fn parse_input(&mut self, example: bool) -> Vec<Vec<i32>> {
-        let raw_data = if example {
-            read_example(self.day())
-        } else {
-            read_input(self.day())
-        };
+    fn parse_input(&mut self, raw_data: &str) -> Vec<Vec<i32>> {
 
         raw_data.lines()
             .filter(|line| !line.is_empty())
@@ -42,24 +147,20 @@ impl DaySolution<Vec<Vec<i32>>, i32> for Day02 {
     }
 
     fn part1(&mut self, input: &Vec<Vec<i32>>) -> i32 {
-        input.iter()
-            .map(|report| self.is_safe(&report))
-            .filter(|&safe| safe)
-            .count() as i32
+        self.count_safe(input, Part::One, 0)
     }
 
     fn part2(&mut self, input: &Vec<Vec<i32>>) -> i32 {
-        input.iter()
-            .map(|report| {
-                (0..report.len())
-                    .map(|i| {
-                        let mut new_report = report.clone();
-                        new_report.remove(i);
-                        new_report
-                    })
-                    .any(|report| self.is_safe(&report))
-            })
-            .filter(|&safe| safe)
-            .count() as i32
+        self.count_safe(input, Part::Two, 1)
+    }
+
+    fn strategies(&self, _part: Part) -> &'static [&'static str] {
+        &STRATEGIES
+    }
+
+    fn use_strategy(&mut self, part: Part, name: &str) -> bool {
+        let Some(check) = CHECKS.into_iter().find(|check| check.name() == name) else { return false };
+        self.checks[part.index()] = check;
+        true
     }
 }
@@ -1,36 +1,165 @@
-use crate::tools::read_raw_data;
 use crate::DaySolution;
-use regex::Regex;
+use std::collections::HashMap;
 
-pub struct Day03 {}
+/// An instruction the scanner looks for: `name(` then `arity` numbers of 1 to 3 digits separated
+/// by commas, then `)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Syntax {
+    pub name: &'static str,
+    pub arity: usize,
+}
+
+pub const MUL: Syntax = Syntax { name: "mul", arity: 2 };
+pub const DO: Syntax = Syntax { name: "do", arity: 0 };
+pub const DONT: Syntax = Syntax { name: "don't", arity: 0 };
+
+pub const SYNTAX: [Syntax; 3] = [MUL, DO, DONT];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(i32, i32),
+    Do,
+    Dont,
+    /// Anything else declared in the scanner's syntax, run by an interpreter extension.
+    Other(&'static str, Vec<i32>),
+}
+
+impl Instruction {
+    fn from_call(name: &'static str, args: Vec<i32>) -> Instruction {
+        match (name, &args[..]) {
+            ("mul", [a, b]) => Instruction::Mul(*a, *b),
+            ("do", []) => Instruction::Do,
+            ("don't", []) => Instruction::Dont,
+            _ => Instruction::Other(name, args),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    /// Byte offset of the instruction in the source.
+    pub offset: usize,
+    pub instruction: Instruction,
+}
+
+/// Walks the corrupted memory byte by byte and yields every well-formed instruction of `syntax`.
+pub struct Scanner<'a> {
+    source: &'a [u8],
+    pos: usize,
+    syntax: &'a [Syntax],
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(source: &'a str, syntax: &'a [Syntax]) -> Self {
+        Scanner {
+            source: source.as_bytes(),
+            pos: 0,
+            syntax,
+        }
+    }
 
-impl Day03 {
-    pub fn get_mul_operations(&self, line: String) -> Vec<String> {
-        Regex::new(r"mul\(\d{1,3},\d{1,3}\)")
-            .unwrap()
-            .find_iter(&line)
-            .map(|mat| mat.as_str().to_string())
-            .collect()
+    fn expect(&self, pos: usize, expected: &[u8]) -> Option<usize> {
+        self.source[pos..].starts_with(expected).then_some(pos + expected.len())
     }
 
-    pub fn get_operations(&self, line: String) -> Vec<String> {
-        Regex::new(r"(mul\(\d{1,3},\d{1,3}\))|(do\(\))|(don't\(\))")
-            .unwrap()
-            .find_iter(&line)
-            .map(|mat| mat.as_str().to_string())
-            .collect()
+    fn number(&self, pos: usize) -> Option<(i32, usize)> {
+        let digits = self.source[pos..].iter()
+            .take(3)
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits == 0 {
+            return None;
+        }
+        let value = self.source[pos..pos + digits].iter().fold(0, |acc, b| acc * 10 + (b - b'0') as i32);
+        Some((value, pos + digits))
     }
 
-    pub fn exec_operation(&self, operation: &str) -> i32 {
-        Regex::new(r"\d{1,3}")
-            .unwrap()
-            .find_iter(operation)
-            .map(|mat| mat.as_str().parse::<i32>().unwrap())
-            .product()
+    /// The instruction starting at `pos` and where it ends.
+    fn call(&self, pos: usize, syntax: &Syntax) -> Option<(Instruction, usize)> {
+        let mut pos = self.expect(pos, syntax.name.as_bytes())?;
+        pos = self.expect(pos, b"(")?;
+        let mut args = Vec::with_capacity(syntax.arity);
+        for i in 0..syntax.arity {
+            if i > 0 {
+                pos = self.expect(pos, b",")?;
+            }
+            let (value, next) = self.number(pos)?;
+            args.push(value);
+            pos = next;
+        }
+        pos = self.expect(pos, b")")?;
+        Some((Instruction::from_call(syntax.name, args), pos))
     }
 }
 
-impl DaySolution<Vec<String>, i32> for Day03 {
+impl Iterator for Scanner<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        while self.pos < self.source.len() {
+            let offset = self.pos;
+            if let Some((instruction, end)) = self.syntax.iter().find_map(|syntax| self.call(offset, syntax)) {
+                self.pos = end;
+                return Some(Token { offset, instruction });
+            }
+            self.pos += 1;
+        }
+        None
+    }
+}
+
+pub type Extension = fn(&mut Interpreter, &[i32]);
+
+pub struct Interpreter {
+    /// Whether `do()` and `don't()` are obeyed.
+    pub conditionals: bool,
+    pub enabled: bool,
+    pub total: i32,
+    extensions: HashMap<&'static str, Extension>,
+}
+
+impl Interpreter {
+    pub fn new(conditionals: bool) -> Self {
+        Interpreter {
+            conditionals,
+            enabled: true,
+            total: 0,
+            extensions: HashMap::new(),
+        }
+    }
+
+    /// Gives a meaning to an [`Instruction::Other`], e.g. `add` next to a `Syntax { name: "add", arity: 2 }`.
+    pub fn define(&mut self, name: &'static str, extension: Extension) {
+        self.extensions.insert(name, extension);
+    }
+
+    /// Whether the instruction had any effect.
+    pub fn execute(&mut self, instruction: &Instruction) -> bool {
+        match instruction {
+            Instruction::Do if self.conditionals => self.enabled = true,
+            Instruction::Dont if self.conditionals => self.enabled = false,
+            Instruction::Do | Instruction::Dont => return false,
+            _ if !self.enabled => return false,
+            Instruction::Mul(a, b) => self.total += a * b,
+            Instruction::Other(name, args) => match self.extensions.get(name) {
+                Some(extension) => extension(self, args),
+                None => return false,
+            },
+        }
+        true
+    }
+
+    pub fn run(&mut self, source: &str, syntax: &[Syntax]) -> i32 {
+        Scanner::new(source, syntax).for_each(|token| {
+            self.execute(&token.instruction);
+        });
+        self.total
+    }
+}
+
+pub struct Day03 {}
+
+impl DaySolution<String, i32> for Day03 {
     fn new() -> Self {
         Day03 {}
     }
@@ -39,38 +168,26 @@ impl DaySolution<Vec<String>, i32> for Day03 {
         3
     }
 
-    fn parse_input(&mut self, example: bool) -> Vec<String> {
-        read_raw_data(self.day(), example)
-            .lines()
-            .filter(|line| !line.is_empty())
-            .map(|line| line.to_string())
-            .collect()
-    }
-
-    fn part1(&mut self, input: &Vec<String>) -> i32 {
-        self.get_mul_operations(input.join(" "))
-            .iter()
-            .map(|operation| self.exec_operation(operation))
-            .sum::<i32>()
-    }
-
-    fn part2(&mut self, input: &Vec<String>) -> i32 {
-        let mut activate = true;
-        self.get_operations(input.join(" "))
-            .iter()
-            .filter_map(|op| match op.as_str() {
-                "do()" => {
-                    activate = true;
-                    None
-                }
-                "don't()" => {
-                    activate = false;
-                    None
-                }
-                _ if activate => Some(op),
-                _ => None,
+    fn parse_input(&mut self, raw_data: &str) -> String {
+        raw_data.to_string()
+    }
+
+    fn part1(&mut self, input: &String) -> i32 {
+        Interpreter::new(false).run(input, &SYNTAX)
+    }
+
+    fn part2(&mut self, input: &String) -> i32 {
+        Interpreter::new(true).run(input, &SYNTAX)
+    }
+
+    fn render(&mut self, input: &String) -> Option<String> {
+        let mut interpreter = Interpreter::new(true);
+        let trace = Scanner::new(input, &SYNTAX)
+            .map(|token| {
+                let executed = interpreter.execute(&token.instruction);
+                format!("{:>5} :: {:?}{}\n", token.offset, token.instruction, if executed { "" } else { " (skipped)" })
             })
-            .map(|operation| self.exec_operation(operation))
-            .sum::<i32>()
+            .collect::<String>();
+        Some(trace)
     }
 }
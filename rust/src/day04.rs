@@ -1,9 +1,9 @@
 use std::collections::HashSet;
-use crate::tools::read_raw_data;
+use crate::parsing::char_grid;
 use crate::DaySolution;
 
-#[derive(Clone)]
-pub struct V2(i16, i16);
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct V2(pub i16, pub i16);
 impl V2 {
     fn x(&self) -> i16 {
         self.0
@@ -13,8 +13,6 @@ impl V2 {
     }
 }
 
-const WORD: [char; 4] = ['X', 'M', 'A', 'S'];
-
 const ALL_DIRECTIONS: [V2; 8] = [
     V2(0, 1),
     V2(1, 0),
@@ -26,22 +24,84 @@ const ALL_DIRECTIONS: [V2; 8] = [
     V2(-1, -1),
 ];
 
-const X_MAX_DIAGRAM_DIRECTIONS: [[V2; 2]; 2] = [
-    [V2(-1, -1), V2(1, 1)],
-    [V2(1, -1), V2(-1, 1)],
-];
+const X_MAS: &str = "M.S\n.A.\nM.S";
 
-pub struct Grid(Vec<Vec<char>>);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WordMatch {
+    pub start: V2,
+    pub direction: V2,
+}
 
-impl Grid {
-    fn new() -> Self {
-        Grid(Vec::new())
+impl WordMatch {
+    fn cells(&self, len: usize) -> impl Iterator<Item=V2> + '_ {
+        (0..len as i16).map(|i| V2(self.start.x() + i * self.direction.x(), self.start.y() + i * self.direction.y()))
     }
+}
 
-    fn add_row(&mut self, row: &str) {
-        self.0.push(row.chars().collect());
+/// A 2D pattern: the letters it requires, by offset from the top-left corner of its bounding
+/// box. Cells not listed are wildcards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stencil(Vec<(V2, char)>);
+
+impl Stencil {
+    /// One line per row, `.` for a wildcard.
+    pub fn parse(pattern: &str) -> Self {
+        let cells = pattern.lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c != '.')
+                    .map(move |(x, c)| (V2(x as i16, y as i16), c))
+            })
+            .collect();
+        Stencil(cells).normalized()
+    }
+
+    fn normalized(mut self) -> Self {
+        let min_x = self.0.iter().map(|(v, _)| v.x()).min().unwrap_or(0);
+        let min_y = self.0.iter().map(|(v, _)| v.y()).min().unwrap_or(0);
+        self.0.iter_mut().for_each(|(v, _)| *v = V2(v.x() - min_x, v.y() - min_y));
+        self.0.sort();
+        self
+    }
+
+    fn rotated(&self) -> Self {
+        Stencil(self.0.iter().map(|(v, c)| (V2(-v.y(), v.x()), *c)).collect()).normalized()
+    }
+
+    fn mirrored(&self) -> Self {
+        Stencil(self.0.iter().map(|(v, c)| (V2(-v.x(), v.y()), *c)).collect()).normalized()
+    }
+
+    /// The distinct stencils among the four rotations of the stencil and of its mirror image.
+    pub fn variants(&self) -> Vec<Stencil> {
+        let mut variants: Vec<Stencil> = vec![];
+        let mut current = self.clone();
+        for i in 0..8 {
+            if i == 4 {
+                current = self.mirrored();
+            }
+            if !variants.contains(&current) {
+                variants.push(current.clone());
+            }
+            current = current.rotated();
+        }
+        variants
     }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StencilMatch {
+    /// Where the top-left corner of the stencil's bounding box lands.
+    pub origin: V2,
+    /// Index in [`Stencil::variants`], 0 when searching without variants.
+    pub variant: usize,
+}
+
+pub struct Grid(Vec<Vec<char>>);
 
+impl Grid {
     fn get(&self, i: &V2) -> Option<char> {
         if i.x() < 0 || i.x() >= self.width() || i.y() < 0 || i.y() >= self.height() {
             return None;
@@ -57,58 +117,67 @@ impl Grid {
         self.0.len() as i16
     }
 
-    fn next_el(&self, current: &V2, direction: &V2) -> Option<V2> {
-        let next = V2(current.x() + direction.x(), current.y() + direction.y());
-        if next.x() < 0 || next.x() >= self.width() || next.y() < 0 || next.y() >= self.height() {
-            None
-        } else {
-            Some(next.clone())
-        }
-    }
-
-    fn check_word(&self, i: &V2) -> i32 {
-        let mut count = 0;
-        for direction in ALL_DIRECTIONS.iter() {
-            let mut l= 0;
-            let mut next = Some(i.clone());
-            while l < WORD.len() && next.is_some() && self.get(&next.clone().unwrap()).unwrap_or(' ') == WORD[l] {
-                if l == WORD.len() - 1 {
-                    count += 1;
-                }
-                next = self.next_el(&next.unwrap(), direction);
-                l+=1;
-            }
-        }
-        count
-    }
-
-    fn check_xmas_diagram(&self, i: &V2) -> bool {
-        if self.get(i).unwrap() != 'A' {
-            return false;
-        }
-        for directions in X_MAX_DIAGRAM_DIRECTIONS.iter() {
-            let chars = directions.iter()
-                .filter_map(|d| self.next_el(&i, d))
-                .filter_map(|next| self.get(&next))
-                .collect::<HashSet<char>>();
-
-            if (!chars.contains(&'M')) || !chars.contains(&'S') {
-                return false;
-            }
-        }
-        true
+    fn positions(&self) -> impl Iterator<Item=V2> + '_ {
+        (0..self.height()).flat_map(move |y| (0..self.width()).map(move |x| V2(x, y)))
     }
 
-    fn for_each<F>(&self, mut func: F)
-    where
-        F: FnMut(&V2, char),
-    {
-        for y in 0..self.height() {
-            for x in 0..self.width() {
-                let i = V2(x, y);
-                func(&i, self.get(&i).unwrap());
-            }
+    /// Every occurrence of `word` in any of the 8 directions, in reading order of their start.
+    /// Without `overlaps`, an occurrence sharing a cell with an earlier one is left out.
+    ///
+    /// An occurrence is a set of cells: a word that reads the same backwards, such as `ABA`, is
+    /// found once, from whichever end comes first in reading order, and a single letter once.
+    pub fn find_word(&self, word: &str, overlaps: bool) -> Vec<WordMatch> {
+        let letters = word.chars().collect::<Vec<char>>();
+        if letters.is_empty() {
+            return vec![];
         }
+        let palindrome = letters.iter().eq(letters.iter().rev());
+        let mut used = HashSet::new();
+        self.positions()
+            .flat_map(|start| ALL_DIRECTIONS.iter().map(move |direction| WordMatch { start, direction: *direction }))
+            .filter(|candidate| {
+                if !palindrome {
+                    return true;
+                }
+                let end = candidate.cells(letters.len()).last().unwrap();
+                let reading_order = |v: V2| (v.y(), v.x());
+                reading_order(candidate.start) < reading_order(end)
+                    || (letters.len() == 1 && candidate.direction == ALL_DIRECTIONS[0])
+            })
+            .filter(|candidate| {
+                candidate.cells(letters.len())
+                    .zip(letters.iter())
+                    .all(|(cell, letter)| self.get(&cell) == Some(*letter))
+            })
+            .filter(|candidate| {
+                if overlaps {
+                    return true;
+                }
+                let cells = candidate.cells(letters.len()).collect::<Vec<V2>>();
+                if cells.iter().any(|cell| used.contains(cell)) {
+                    return false;
+                }
+                used.extend(cells);
+                true
+            })
+            .collect()
+    }
+
+    /// Every place the stencil fits, also trying its rotations and mirror images with `variants`.
+    pub fn find_stencil(&self, stencil: &Stencil, variants: bool) -> Vec<StencilMatch> {
+        let stencils = if variants { stencil.variants() } else { vec![stencil.clone()] };
+        self.positions()
+            .flat_map(|origin| {
+                stencils.iter()
+                    .enumerate()
+                    .filter(move |(_, stencil)| {
+                        stencil.0.iter().all(|(offset, c)| {
+                            self.get(&V2(origin.x() + offset.x(), origin.y() + offset.y())) == Some(*c)
+                        })
+                    })
+                    .map(move |(variant, _)| StencilMatch { origin, variant })
+            })
+            .collect()
     }
 }
 
@@ -124,28 +193,15 @@ impl DaySolution<Grid, i32> for Day04 {
         4
     }
 
-    fn parse_input(&mut self, example: bool) -> Grid {
-        let raw_data = read_raw_data(self.day(), example);
-        let mut grid = Grid::new();
-        for line in raw_data.lines() {
-            grid.add_row(line);
-        }
-        grid
+    fn parse_input(&mut self, raw_data: &str) -> Grid {
+        Grid(char_grid(raw_data))
     }
 
     fn part1(&mut self, input: &Grid) -> i32 {
-        let mut count = 0;
-        input.for_each(|i, _c| {
-            count += input.check_word(i);
-        });
-        count
+        input.find_word("XMAS", true).len() as i32
     }
 
     fn part2(&mut self, input: &Grid) -> i32 {
-        let mut count = 0;
-        input.for_each(|i, _c| {
-            count += if input.check_xmas_diagram(i) { 1 } else { 0 };
-        });
-        count
+        input.find_stencil(&Stencil::parse(X_MAS), true).len() as i32
     }
 }
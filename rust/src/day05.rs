@@ -1,4 +1,5 @@
-use crate::tools::read_raw_data;
+use crate::parse;
+use crate::parsing::{blocks, ints};
 use crate::DaySolution;
 use std::cmp::Ordering;
 use std::collections::HashMap;
@@ -10,22 +11,60 @@ impl SortRule {
     }
 
     fn add_rule(&mut self, str: &str) {
-        let v = str.split("|").map(|s| { s.parse::<i32>().unwrap() }).collect::<Vec<i32>>();
-        self.0.insert((v[0], v[1]), Ordering::Less);
-        self.0.insert((v[1], v[0]), Ordering::Greater);
+        let (before, after): (i32, i32) = parse!("{}|{}", str).unwrap();
+        self.0.insert((before, after), Ordering::Less);
+        self.0.insert((after, before), Ordering::Greater);
     }
 
     fn get_rule(&self, a: i32, b: i32) -> Ordering {
         *self.0.get(&(a, b)).unwrap_or(&Ordering::Equal)
     }
 
-    fn is_sorted(&self, list: &Vec<i32>) -> bool {
+    fn is_sorted(&self, list: &[i32]) -> bool {
         list.windows(2).all(|w| { self.get_rule(w[0], w[1]) == Ordering::Less })
     }
 }
 
 pub struct Data(SortRule, Vec<Vec<i32>>);
 
+impl Data {
+    pub fn is_sorted(&self, update: &[i32]) -> bool {
+        self.0.is_sorted(update)
+    }
+
+    /// The update sorted with the rules as a comparator, pages without a rule between them
+    /// counting as equal.
+    pub fn reorder(&self, update: &[i32]) -> Vec<i32> {
+        let mut sorted = update.to_vec();
+        sorted.sort_by(|a, b| { self.0.get_rule(*a, *b) });
+        sorted
+    }
+
+    /// Whether no rule puts a later page before an earlier one, checking every pair.
+    pub fn is_sorted_by_brute_force(&self, update: &[i32]) -> bool {
+        (0..update.len()).all(|i| {
+            (i + 1..update.len()).all(|j| self.0.get_rule(update[j], update[i]) != Ordering::Less)
+        })
+    }
+
+    /// The first permutation of the update, in lexicographic order of positions, that breaks
+    /// no rule. Meant for a handful of pages: it tries all `n!` of them.
+    pub fn reorder_by_brute_force(&self, update: &[i32]) -> Option<Vec<i32>> {
+        let mut order = (0..update.len()).collect::<Vec<usize>>();
+        loop {
+            let permutation = order.iter().map(|i| update[*i]).collect::<Vec<i32>>();
+            if self.is_sorted_by_brute_force(&permutation) {
+                return Some(permutation);
+            }
+            // Next permutation of `order`, or stop after the last one.
+            let i = (1..order.len()).rev().find(|i| order[i - 1] < order[*i])?;
+            let j = (i..order.len()).rev().find(|j| order[i - 1] < order[*j])?;
+            order.swap(i - 1, j);
+            order[i..].reverse();
+        }
+    }
+}
+
 pub struct Day05;
 
 impl DaySolution<Data, i32> for Day05 {
@@ -37,22 +76,11 @@ impl DaySolution<Data, i32> for Day05 {
         5
     }
 
-    fn parse_input(&mut self, example: bool) -> Data {
-        let raw_data = read_raw_data(self.day(), example);
-        let mut read_rules = true;
+    fn parse_input(&mut self, raw_data: &str) -> Data {
+        let [rules, updates] = &blocks(raw_data)[..] else { panic!("Invalid input") };
         let mut data = Data(SortRule::new(), Vec::new());
-        raw_data.lines().for_each(|line| {
-            if line.trim().is_empty() {
-                read_rules = false;
-                return;
-            }
-
-            if read_rules {
-                data.0.add_rule(line);
-            } else {
-                data.1.push(line.split(",").map(|s| { s.parse::<i32>().unwrap() }).collect());
-            }
-        });
+        rules.iter().for_each(|line| data.0.add_rule(line));
+        updates.iter().for_each(|line| data.1.push(ints(line).unwrap()));
         data
     }
 
@@ -71,8 +99,7 @@ impl DaySolution<Data, i32> for Day05 {
         lists.iter()
             .filter(|list| { !rules.is_sorted(list) })
             .map(|list| {
-                let mut sorted = list.clone();
-                sorted.sort_by(|a, b| { rules.get_rule(*a, *b) });
+                let sorted = input.reorder(list);
                 sorted[sorted.len() / 2]
             })
             .sum()
@@ -1,29 +1,26 @@
-use std::collections::HashSet;
-use crate::tools::read_raw_data;
-use crate::DaySolution;
+use crate::{cancel, parallel, DaySolution, Part, DEFAULT_STRATEGY};
 
 const OBSTACLE: char = '#';
 const GUARD: [char; 4] = ['^', '>', 'v', '<'];
 
-#[derive(Clone, Debug)]
-struct Position {
-    x: usize,
-    y: usize,
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
 }
 
-#[derive(PartialEq, Debug)]
-enum Op {
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Op {
     TurnRight,
     Forward,
-    NoOp,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up = 0,
+    Right = 1,
+    Down = 2,
+    Left = 3,
 }
 impl Direction {
     fn from(c: &char) -> Direction {
@@ -35,21 +32,71 @@ impl Direction {
             _ => panic!("Invalid direction"),
         }
     }
+
+    fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    fn mask(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Guard {
+    pub position: Position,
+    pub direction: Direction,
+}
+
+/// One move of the guard: where it stands and faces once `op` is done.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub guard: Guard,
+    pub op: Op,
+}
+
+/// The guard's walk, ending when it leaves the map. It never ends if the guard is stuck in a loop.
+pub struct Walk<'a> {
+    data: &'a Data,
+    guard: Guard,
+}
+
+impl Iterator for Walk<'_> {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        let op = match self.data.ahead(self.guard.position, self.guard.direction)? {
+            Position { x, y } if self.data.is_obstacle(x, y) => {
+                self.guard.direction = self.guard.direction.turn_right();
+                Op::TurnRight
+            }
+            position => {
+                self.guard.position = position;
+                Op::Forward
+            }
+        };
+        Some(Step { guard: self.guard, op })
+    }
 }
 
-#[derive(Clone)]
 pub struct Data {
     grid: Vec<Vec<bool>>,
-    current: Position,
-    direction: Direction,
+    start: Guard,
 }
 
 impl Data {
     fn new() -> Self {
         Data {
             grid: Vec::new(),
-            current: Position { x: 0, y: 0 },
-            direction: Direction::Up,
+            start: Guard {
+                position: Position { x: 0, y: 0 },
+                direction: Direction::Up,
+            },
         }
     }
 
@@ -57,10 +104,9 @@ impl Data {
         let row: Vec<bool> = line.chars().map(|c| { c == OBSTACLE }).collect();
         GUARD.iter().for_each(|&guard| {
             if let Some(idx) = line.find(guard) {
-                self.direction = Direction::from(&guard);
-                self.current = Position {
-                    x: idx,
-                    y: self.grid.len(),
+                self.start = Guard {
+                    position: Position { x: idx, y: self.grid.len() },
+                    direction: Direction::from(&guard),
                 };
             }
         });
@@ -76,91 +122,200 @@ impl Data {
         self.grid.len()
     }
 
+    fn index(&self, position: Position) -> usize {
+        position.y * self.width() + position.x
+    }
+
     fn is_obstacle(&self, x: usize, y: usize) -> bool {
         self.grid[y][x]
     }
 
-    fn next(&mut self) -> Op {
-        let Position { x, y } = self.current;
-        match self.direction {
-            Direction::Up => {
-                if y == 0 {
-                    Op::NoOp
-                } else if self.is_obstacle(x, y - 1) {
-                    self.direction = Direction::Right;
-                    Op::TurnRight
-                } else {
-                    self.current.y -= 1;
-                    Op::Forward
-                }
-            }
-            Direction::Right => {
-                if x == self.width() - 1 {
-                    Op::NoOp
-                } else if self.is_obstacle(x + 1, y) {
-                    self.direction = Direction::Down;
-                    Op::TurnRight
-                } else {
-                    self.current.x += 1;
-                    Op::Forward
-                }
-            }
-            Direction::Down => {
-                if y == self.height() - 1 {
-                    Op::NoOp
-                } else if self.is_obstacle(x, y + 1) {
-                    self.direction = Direction::Left;
-                    Op::TurnRight
-                } else {
-                    self.current.y += 1;
-                    Op::Forward
-                }
-            }
-            Direction::Left => {
-                if x == 0 {
-                    Op::NoOp
-                } else if self.is_obstacle(x - 1, y) {
-                    self.direction = Direction::Up;
-                    Op::TurnRight
-                } else {
-                    self.current.x -= 1;
-                    Op::Forward
-                }
+    /// The cell in front of `position`, or `None` when that would leave the map.
+    fn ahead(&self, position: Position, direction: Direction) -> Option<Position> {
+        let Position { x, y } = position;
+        match direction {
+            Direction::Up if y > 0 => Some(Position { x, y: y - 1 }),
+            Direction::Right if x + 1 < self.width() => Some(Position { x: x + 1, y }),
+            Direction::Down if y + 1 < self.height() => Some(Position { x, y: y + 1 }),
+            Direction::Left if x > 0 => Some(Position { x: x - 1, y }),
+            _ => None,
+        }
+    }
+
+    pub fn walk(&self) -> Walk<'_> {
+        Walk {
+            data: self,
+            guard: self.start,
+        }
+    }
+
+    /// The guard cannot be obstructed where it stands, nor where an obstacle already is.
+    fn can_obstruct(&self, position: Position) -> bool {
+        position != self.start.position && !self.is_obstacle(position.x, position.y)
+    }
+
+    pub fn start(&self) -> Guard {
+        self.start
+    }
+
+    /// Where a new obstacle can change the walk: the cells of the original path, each paired with
+    /// the guard just before it first steps there, since the walk up to that point stays the same.
+    pub fn obstruction_candidates(&self) -> Vec<(Guard, Position)> {
+        let mut tried = vec![false; self.width() * self.height()];
+        let mut candidates = vec![];
+        let mut before = self.start;
+        for Step { guard, op } in self.walk() {
+            let idx = self.index(guard.position);
+            if op == Op::Forward && !tried[idx] && self.can_obstruct(guard.position) {
+                tried[idx] = true;
+                candidates.push((before, guard.position));
             }
+            before = guard;
         }
+        candidates
+    }
+
+    fn with_obstacle(&self, position: Position) -> Data {
+        let mut grid = self.grid.clone();
+        grid[position.y][position.x] = true;
+        Data { grid, start: self.start }
+    }
+
+    /// Cells where a new obstacle traps the guard in a loop, walking a copy of the map with the
+    /// obstacle from the start for every cell of the original path.
+    fn loops_by_brute_force(&self) -> usize {
+        let path = self.walk_into().unwrap();
+        (0..self.height())
+            .flat_map(|y| (0..self.width()).map(move |x| Position { x, y }))
+            .filter(|position| path[self.index(*position)] != 0 && self.can_obstruct(*position))
+            .filter(|position| self.with_obstacle(*position).walk_into().is_none())
+            .count()
     }
 
-    fn walk_into(&self) -> Option<Vec<Vec<HashSet<Direction>>>> {
-        let mut visited = vec![vec![HashSet::new(); self.width()]; self.height()];
+    /// Walks the guard out of the map and returns, per cell, the bitmask of directions it was
+    /// seen facing there, or `None` if the guard ends up in a loop. Stops early when cancelled.
+    fn walk_into(&self) -> Option<Vec<u8>> {
+        let mut visited = vec![0_u8; self.width() * self.height()];
 
-        let mut input = self.clone();
-        visited[*&input.current.y][*&input.current.x].insert(input.direction.clone());
+        visited[self.index(self.start.position)] |= self.start.direction.mask();
 
-        while Op::NoOp != input.next() {
-            if visited[*&input.current.y][*&input.current.x].contains(&input.direction.clone()) {
+        for Step { guard, .. } in self.walk() {
+            if cancel::cancelled() {
+                break;
+            }
+            let idx = self.index(guard.position);
+            if visited[idx] & guard.direction.mask() != 0 {
                 return None;
             }
-            visited[*&input.current.y][*&input.current.x].insert(input.direction.clone());
+            visited[idx] |= guard.direction.mask();
         }
 
         Some(visited)
+    }
+}
+
+/// For each cell and direction, the last cell the guard reaches before bumping into an obstacle,
+/// `None` when it walks off the map instead.
+struct JumpTable<'a> {
+    data: &'a Data,
+    jumps: Vec<[Option<Position>; 4]>,
+}
+
+impl<'a> JumpTable<'a> {
+    fn new(data: &'a Data) -> Self {
+        let (width, height) = (data.width(), data.height());
+        let mut table = JumpTable {
+            data,
+            jumps: vec![[None; 4]; width * height],
+        };
+
+        // Each cell only depends on the cell ahead of it, so sweep from the edge the guard faces.
+        let rows_down = (0..height).flat_map(|y| (0..width).map(move |x| Position { x, y }));
+        let rows_up = (0..height).rev().flat_map(|y| (0..width).map(move |x| Position { x, y }));
+        let cols_right = (0..width).flat_map(|x| (0..height).map(move |y| Position { x, y }));
+        let cols_left = (0..width).rev().flat_map(|x| (0..height).map(move |y| Position { x, y }));
+
+        table.fill(Direction::Up, rows_down);
+        table.fill(Direction::Down, rows_up);
+        table.fill(Direction::Left, cols_right);
+        table.fill(Direction::Right, cols_left);
+        table
+    }
+
+    fn fill(&mut self, direction: Direction, order: impl Iterator<Item=Position>) {
+        for position in order {
+            let jump = match self.data.ahead(position, direction) {
+                None => None,
+                Some(Position { x, y }) if self.data.is_obstacle(x, y) => Some(position),
+                Some(ahead) => self.jumps[self.data.index(ahead)][direction as usize],
+            };
+            let idx = self.data.index(position);
+            self.jumps[idx][direction as usize] = jump;
+        }
+    }
+
+    /// Like the precomputed jump, but also stopping in front of the `extra` obstacle when it
+    /// stands between `guard` and where it would otherwise stop.
+    fn jump(&self, guard: Guard, extra: Position) -> Option<Position> {
+        let from = guard.position;
+        let target = self.jumps[self.data.index(from)][guard.direction as usize];
+        match guard.direction {
+            Direction::Up if extra.x == from.x && extra.y < from.y && target.is_none_or(|t| extra.y >= t.y) => {
+                Some(Position { x: from.x, y: extra.y + 1 })
+            }
+            Direction::Down if extra.x == from.x && extra.y > from.y && target.is_none_or(|t| extra.y <= t.y) => {
+                Some(Position { x: from.x, y: extra.y - 1 })
+            }
+            Direction::Left if extra.y == from.y && extra.x < from.x && target.is_none_or(|t| extra.x >= t.x) => {
+                Some(Position { x: extra.x + 1, y: from.y })
+            }
+            Direction::Right if extra.y == from.y && extra.x > from.x && target.is_none_or(|t| extra.x <= t.x) => {
+                Some(Position { x: extra.x - 1, y: from.y })
+            }
+            _ => target,
+        }
+    }
 
+    /// Replays the walk from `guard`, facing the new `obstacle`, jumping from turn to turn.
+    /// Only turns are recorded in `visited`, which is enough to detect a loop.
+    fn loops(&self, mut guard: Guard, obstacle: Position, visited: &mut [u8]) -> bool {
+        visited.fill(0);
+        while let Some(position) = self.jump(guard, obstacle) {
+            if cancel::cancelled() {
+                return false;
+            }
+            guard = Guard {
+                position,
+                direction: guard.direction.turn_right(),
+            };
+            let idx = self.data.index(position);
+            if visited[idx] & guard.direction.mask() != 0 {
+                return true;
+            }
+            visited[idx] |= guard.direction.mask();
+        }
+        false
     }
 }
 
-pub struct Day06;
+const PART2_STRATEGIES: [&str; 2] = ["jump-table", "naive"];
+
+pub struct Day06 {
+    /// Whether part 2 walks a copy of the map with each candidate obstacle from the start.
+    naive: bool,
+}
 
 impl DaySolution<Data, usize> for Day06 {
     fn new() -> Self {
-        Day06 {}
+        Day06 { naive: false }
     }
 
     fn day(&self) -> u16 {
         6
     }
 
-    fn parse_input(&mut self, example: bool) -> Data {
-        read_raw_data(self.day(), example)
+    fn parse_input(&mut self, raw_data: &str) -> Data {
+        raw_data
             .lines()
             .fold(Data::new(), |mut data, line| {
                 data.add_line(line);
@@ -170,26 +325,42 @@ impl DaySolution<Data, usize> for Day06 {
 
     fn part1(&mut self, input: &Data) -> usize {
         input.walk_into().unwrap().iter()
-            .flatten()
-            .filter(|directions| directions.len() >= 1)
+            .filter(|&&directions| directions != 0)
             .count()
     }
 
-
+    /// Each candidate obstacle is replayed from the step just before the guard would reach it.
     fn part2(&mut self, input: &Data) -> usize {
-        input.grid.iter()
-            .enumerate()
-            .map(|(y, row)| row.iter().enumerate().map(move |(x, is_obstacle)| {(x, y, is_obstacle)}))
-            .flatten()
-            .filter(|(_, _, is_obstacle)| !**is_obstacle)
-            .filter(|(x, y, _)| {
-                let mut data = input.clone();
-                if data.grid[*y][*x] {
-                    return false;
-                }
-                data.grid[*y][*x] = true;
-                data.walk_into().is_none()
-            })
-            .count()
+        if self.naive {
+            return input.loops_by_brute_force();
+        }
+        let jumps = JumpTable::new(input);
+        let candidates = input.obstruction_candidates();
+
+        // The candidates are independent, so they may be replayed on several threads.
+        parallel::map_chunks(&candidates, |chunk| {
+            let mut visited = vec![0_u8; input.width() * input.height()];
+            vec![chunk.iter().filter(|(before, obstacle)| jumps.loops(*before, *obstacle, &mut visited)).count()]
+        })
+            .iter()
+            .sum()
+    }
+
+    fn strategies(&self, part: Part) -> &'static [&'static str] {
+        match part {
+            Part::One => &[DEFAULT_STRATEGY],
+            Part::Two => &PART2_STRATEGIES,
+        }
+    }
+
+    fn use_strategy(&mut self, part: Part, name: &str) -> bool {
+        match (part, name) {
+            (Part::One, DEFAULT_STRATEGY) => true,
+            (Part::Two, "jump-table") | (Part::Two, "naive") => {
+                self.naive = name == "naive";
+                true
+            }
+            _ => false,
+        }
     }
 }
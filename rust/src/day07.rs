@@ -1,57 +1,307 @@
-use crate::tools::read_raw_data;
-use crate::DaySolution;
+use crate::parse;
+use crate::parsing::ints;
+use crate::{cancel, parallel, DaySolution};
+use std::fmt::{Display, Formatter};
 
-#[derive(Clone, Debug)]
-enum Op {
+/// What the backward search learns from undoing an operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Undo {
+    /// The only left operand giving the result.
+    Operand(i128),
+    /// No left operand gives the result.
+    Impossible,
+    /// The left operand is not unique or cannot be computed, the solver has to search forwards.
+    Unsupported,
+}
+
+/// A binary operator usable in calibration equations. Implement it to try puzzle variants
+/// without touching [`Op`].
+pub trait Operator: Sync {
+    fn symbol(&self) -> &str;
+
+    /// Binding strength when evaluating with [`Evaluation::Precedence`], higher binds tighter.
+    fn precedence(&self) -> u8;
+
+    /// Whether a chain of this operator groups from the right, as `2 ** 3 ** 3` does.
+    fn right_associative(&self) -> bool {
+        false
+    }
+
+    /// Whether non-negative operands always give a non-negative result, which lets the backward
+    /// search drop negative targets when every number is non-negative.
+    fn keeps_non_negative(&self) -> bool {
+        false
+    }
+
+    /// `None` when the result is undefined or overflows.
+    fn apply(&self, a: i128, b: i128) -> Option<i128>;
+
+    fn undo(&self, _res: i128, _b: i128) -> Undo {
+        Undo::Unsupported
+    }
+}
+
+// Only Add, Mul and Concat are part of the puzzle, the others are there for variants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
     Add,
+    Sub,
     Mul,
+    Div,
     Concat,
+    Xor,
+    Pow,
+}
+
+/// The power of ten that shifts `a` left by as many digits as `b` has.
+fn shift(b: i128) -> Option<i128> {
+    let mut shift: i128 = 10;
+    while shift <= b {
+        shift = shift.checked_mul(10)?;
+    }
+    Some(shift)
+}
+
+fn undo_if(exact: bool, a: i128) -> Undo {
+    if exact {
+        Undo::Operand(a)
+    } else {
+        Undo::Impossible
+    }
+}
+
+impl Operator for Op {
+    fn symbol(&self) -> &str {
+        match self {
+            Op::Add => "+",
+            Op::Sub => "-",
+            Op::Mul => "*",
+            Op::Div => "/",
+            Op::Concat => "||",
+            Op::Xor => "^",
+            Op::Pow => "**",
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Op::Concat => 0,
+            Op::Xor => 1,
+            Op::Add | Op::Sub => 2,
+            Op::Mul | Op::Div => 3,
+            Op::Pow => 4,
+        }
+    }
+
+    fn right_associative(&self) -> bool {
+        *self == Op::Pow
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        *self != Op::Sub
+    }
+
+    fn apply(&self, a: i128, b: i128) -> Option<i128> {
+        match self {
+            Op::Add => a.checked_add(b),
+            Op::Sub => a.checked_sub(b),
+            Op::Mul => a.checked_mul(b),
+            Op::Div => a.checked_div(b),
+            Op::Concat if a >= 0 && b >= 0 => a.checked_mul(shift(b)?)?.checked_add(b),
+            Op::Concat => None,
+            Op::Xor => Some(a ^ b),
+            Op::Pow => a.checked_pow(b.try_into().ok()?),
+        }
+    }
+
+    fn undo(&self, res: i128, b: i128) -> Undo {
+        match self {
+            Op::Add => res.checked_sub(b).map_or(Undo::Impossible, Undo::Operand),
+            Op::Sub => res.checked_add(b).map_or(Undo::Impossible, Undo::Operand),
+            Op::Mul if b == 0 => if res == 0 { Undo::Unsupported } else { Undo::Impossible },
+            Op::Mul => res.checked_div(b).map_or(Undo::Impossible, |a| undo_if(res.checked_rem(b) == Some(0), a)),
+            Op::Concat if b < 0 || res < b => Undo::Impossible,
+            Op::Concat => shift(b).map_or(Undo::Impossible, |shift| undo_if((res - b) % shift == 0, (res - b) / shift)),
+            Op::Xor => Undo::Operand(res ^ b),
+            Op::Div | Op::Pow => Undo::Unsupported,
+        }
+    }
 }
 
-impl Op {
-    fn apply(&self, a: i128, b: i128) -> i128 {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Evaluation {
+    /// The puzzle's rule: operators are applied in reading order.
+    LeftToRight,
+    /// Higher precedence operators first, equal ones left to right unless right associative.
+    Precedence,
+}
+
+impl Evaluation {
+    pub fn evaluate(&self, numbers: &[i128], ops: &[&dyn Operator]) -> Option<i128> {
         match self {
-            Op::Add => a + b,
-            Op::Mul => a * b,
-            Op::Concat => format!("{}{}", a, b).parse().unwrap()
+            Evaluation::LeftToRight => ops.iter()
+                .zip(numbers.iter().skip(1))
+                .try_fold(numbers[0], |acc, (op, n)| op.apply(acc, *n)),
+            Evaluation::Precedence => {
+                let mut values = vec![numbers[0]];
+                let mut pending: Vec<&dyn Operator> = vec![];
+                let reduce = |values: &mut Vec<i128>, op: &dyn Operator| {
+                    let b = values.pop().unwrap();
+                    let a = values.pop().unwrap();
+                    values.push(op.apply(a, b)?);
+                    Some(())
+                };
+                for (op, n) in ops.iter().zip(numbers.iter().skip(1)) {
+                    let binds_before = |top: &&&dyn Operator| {
+                        top.precedence() > op.precedence() || (top.precedence() == op.precedence() && !op.right_associative())
+                    };
+                    while let Some(top) = pending.last().filter(binds_before) {
+                        reduce(&mut values, *top)?;
+                        pending.pop();
+                    }
+                    pending.push(*op);
+                    values.push(*n);
+                }
+                while let Some(top) = pending.pop() {
+                    reduce(&mut values, top)?;
+                }
+                values.pop()
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct OperatorSet<'a> {
+    ops: Vec<&'a dyn Operator>,
+    evaluation: Evaluation,
+}
+
+impl<'a> OperatorSet<'a> {
+    pub fn new(ops: &[&'a dyn Operator], evaluation: Evaluation) -> Self {
+        Self {
+            ops: ops.to_vec(),
+            evaluation,
         }
     }
+
+    fn keeps_non_negative(&self) -> bool {
+        self.ops.iter().all(|op| op.keeps_non_negative())
+    }
 }
 
-const OPS_1: [Op; 2] = [Op::Add, Op::Mul];
-const OPS_2: [Op; 3] = [Op::Add, Op::Mul, Op::Concat];
+pub const OPS_1: [&dyn Operator; 2] = [&Op::Add, &Op::Mul];
+pub const OPS_2: [&dyn Operator; 3] = [&Op::Add, &Op::Mul, &Op::Concat];
+
+/// A satisfied calibration line, `ops[i]` sitting between `numbers[i]` and `numbers[i + 1]`.
+#[derive(Clone)]
+pub struct Equation<'a> {
+    pub target: i128,
+    pub numbers: Vec<i128>,
+    pub ops: Vec<&'a dyn Operator>,
+    pub evaluation: Evaluation,
+}
+
+impl Equation<'_> {
+    pub fn evaluate(&self) -> Option<i128> {
+        self.evaluation.evaluate(&self.numbers, &self.ops)
+    }
+}
+
+impl Display for Equation<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {}", self.target, self.numbers[0])?;
+        for (op, n) in self.ops.iter().zip(self.numbers.iter().skip(1)) {
+            write!(f, " {} {}", op.symbol(), n)?;
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug)]
 pub struct Problem(i128, Vec<i128>);
 impl From<&str> for Problem {
     fn from(s: &str) -> Self {
-        let [target_s, numbers_s] = s.split(':').collect::<Vec<&str>>()[..] else { panic!("Invalid input") };
-
-        Problem(
-            target_s.parse().unwrap(),
-            numbers_s.trim().split(' ').map(|n| n.parse().unwrap()).collect(),
-        )
+        let (target, numbers): (i128, String) = parse!("{}:{}", s).unwrap();
+        let numbers = ints(&numbers).unwrap();
+        assert!(!numbers.is_empty(), "Invalid equation, no numbers: {}", s);
+        Problem(target, numbers)
     }
 }
 impl Problem {
-    fn check(&self, base_opts: Vec<Op>) -> bool {
-        self.check_rec(base_opts, self.1[0], 1).is_some()
+    /// Left to right equations are searched from the last number back to the first, undoing each
+    /// operator on the target: most branches die right away on a failed subtraction, division or
+    /// suffix match. Precedence, or an operator without a unique inverse, falls back to trying
+    /// every combination forwards.
+    pub fn solve<'a>(&self, set: &OperatorSet<'a>) -> Option<Equation<'a>> {
+        if cancel::cancelled() {
+            return None;
+        }
+        let mut chosen = Vec::with_capacity(self.1.len());
+        let non_negative = set.keeps_non_negative() && self.1.iter().all(|n| *n >= 0);
+        let backward = match set.evaluation {
+            Evaluation::LeftToRight => self.solve_backward(&set.ops, non_negative, self.0, self.1.len() - 1, &mut chosen),
+            Evaluation::Precedence => None,
+        };
+        match backward {
+            Some(true) => chosen.reverse(),
+            Some(false) => return None,
+            None => {
+                chosen.clear();
+                if !self.solve_forward(set, &mut chosen) {
+                    return None;
+                }
+            }
+        }
+        let equation = Equation {
+            target: self.0,
+            numbers: self.1.clone(),
+            ops: chosen,
+            evaluation: set.evaluation,
+        };
+        debug_assert_eq!(equation.evaluate(), Some(equation.target));
+        Some(equation)
+    }
+
+    /// Pushes the chosen operators last to first, `None` when it meets an operator it cannot
+    /// invert. With `non_negative` operands and operators, a negative target cannot be reached.
+    fn solve_backward<'a>(&self, ops: &[&'a dyn Operator], non_negative: bool, target: i128, idx: usize, chosen: &mut Vec<&'a dyn Operator>) -> Option<bool> {
+        if idx == 0 {
+            return Some(target == self.1[0]);
+        }
+        for op in ops {
+            match op.undo(target, self.1[idx]) {
+                Undo::Operand(previous) if non_negative && previous < 0 => {}
+                Undo::Operand(previous) => {
+                    chosen.push(*op);
+                    if self.solve_backward(ops, non_negative, previous, idx - 1, chosen)? {
+                        return Some(true);
+                    }
+                    chosen.pop();
+                }
+                Undo::Impossible => {}
+                Undo::Unsupported => return None,
+            }
+        }
+        Some(false)
     }
 
-    fn check_rec(&self, base_opts: Vec<Op>, previous_res: i128, idx: usize) -> Option<i128> {
-        if idx == self.1.len() {
-            if previous_res == self.0 {
-                Some(previous_res)
-            } else {
-                None
+    /// Pushes the chosen operators first to last.
+    fn solve_forward<'a>(&self, set: &OperatorSet<'a>, chosen: &mut Vec<&'a dyn Operator>) -> bool {
+        if cancel::cancelled() {
+            return false;
+        }
+        if chosen.len() == self.1.len() - 1 {
+            return set.evaluation.evaluate(&self.1, chosen) == Some(self.0);
+        }
+        for op in &set.ops {
+            chosen.push(*op);
+            if self.solve_forward(set, chosen) {
+                return true;
             }
-        } else {
-            base_opts.iter()
-                .filter_map(|op| {
-                    self.check_rec(base_opts.clone(), op.apply(previous_res, self.1[idx]), idx + 1)
-                })
-                .next()
+            chosen.pop();
         }
+        false
     }
 }
 
@@ -68,24 +318,21 @@ impl DaySolution<Data, i128> for Day07 {
         7
     }
 
-    fn parse_input(&mut self, example: bool) -> Data {
-        let raw_data = read_raw_data(7, example);
+    fn parse_input(&mut self, raw_data: &str) -> Data {
         raw_data.lines().map(|l| l.into()).collect()
     }
 
     fn part1(&mut self, input: &Data) -> i128 {
-        input
+        let set = OperatorSet::new(&OPS_1, Evaluation::LeftToRight);
+        parallel::map(input, |p| p.solve(&set).map_or(0, |equation| equation.target))
             .iter()
-            .filter(|p| p.check(OPS_1.to_vec()))
-            .map(|problem| problem.0)
             .sum()
     }
 
     fn part2(&mut self, input: &Data) -> i128 {
-        input
+        let set = OperatorSet::new(&OPS_2, Evaluation::LeftToRight);
+        parallel::map(input, |p| p.solve(&set).map_or(0, |equation| equation.target))
             .iter()
-            .filter(|p| p.check(OPS_2.to_vec()))
-            .map(|problem| problem.0)
             .sum()
     }
 }
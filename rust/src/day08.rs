@@ -1,18 +1,38 @@
-use crate::tools::read_raw_data;
-use crate::DaySolution;
-use std::collections::{HashMap, HashSet};
+use crate::{DaySolution, Part, DEFAULT_STRATEGY};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::{Add, Sub};
 
-#[derive(Clone, Copy, Hash, Eq, PartialEq)]
-struct Position {
-    x: i32,
-    y: i32,
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
 }
 
 impl Position {
-    fn new(x: i32, y: i32) -> Self {
+    pub fn new(x: i32, y: i32) -> Self {
         Self { x, y }
     }
+
+    /// The smallest grid step along the same line.
+    fn reduced(self) -> Self {
+        let divisor = gcd(self.x.abs(), self.y.abs());
+        Position::new(self.x / divisor, self.y / divisor)
+    }
+}
+
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// One antinode on each side, as far from the nearest antenna as the antennas are apart.
+    Pair,
+    /// Every grid point on the line through both antennas.
+    Resonance,
+    /// The puzzle text taken literally: multiples of the full antenna distance only, which
+    /// skips in-between points when both coordinates of the distance share a factor.
+    LiteralResonance,
 }
 
 impl Add for Position {
@@ -31,11 +51,49 @@ impl Sub for Position {
     }
 }
 
+/// Antinodes grouped by the frequency of the antennas that create them.
+pub struct AntinodeReport {
+    pub by_frequency: HashMap<char, HashSet<Position>>,
+}
+
+impl AntinodeReport {
+    pub fn all(&self) -> HashSet<Position> {
+        self.by_frequency.values().flatten().copied().collect()
+    }
+
+    /// How many antinodes each pair of frequencies has in common, smaller frequency first.
+    pub fn overlaps(&self) -> BTreeMap<(char, char), usize> {
+        let mut frequencies = self.by_frequency.keys().copied().collect::<Vec<char>>();
+        frequencies.sort();
+        pairs(&frequencies)
+            .map(|(a, b)| ((a, b), self.by_frequency[&a].intersection(&self.by_frequency[&b]).count()))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    /// The overlaps, one line per pair of frequencies.
+    pub fn describe_overlaps(&self) -> String {
+        let overlaps = self.overlaps();
+        if overlaps.is_empty() {
+            return "No antinode shared between frequencies\n".to_string();
+        }
+        overlaps.iter()
+            .map(|((a, b), count)| format!("Shared by {} and {}: {}\n", a, b, count))
+            .collect()
+    }
+}
+
+/// Every unordered pair of distinct elements.
+fn pairs<T: Copy>(items: &[T]) -> impl Iterator<Item=(T, T)> + '_ {
+    items.iter()
+        .enumerate()
+        .flat_map(move |(i, a)| items[i + 1..].iter().map(move |b| (*a, *b)))
+}
+
 pub struct Data {
     width: i32,
     height: i32,
-    all: HashSet<Position>,
-    indexed: HashMap<char, HashSet<Position>>,
+    indexed: HashMap<char, Vec<Position>>,
 }
 
 impl Data {
@@ -43,34 +101,32 @@ impl Data {
         Self {
             width,
             height,
-            all: HashSet::new(),
             indexed: HashMap::new(),
         }
     }
 
     fn add_antenna(&mut self, c: char, x: i32, y: i32) {
         let pos = Position::new(x, y);
-        self.all.insert(pos);
         self.indexed
             .entry(c)
-            .or_insert(HashSet::new())
-            .insert(pos);
+            .or_default()
+            .push(pos);
     }
 
     fn in_grid(&self, pos: &Position) -> bool {
         pos.x >= 0 && pos.x < self.width && pos.y >= 0 && pos.y < self.height
     }
 
-    fn calculate_antinodes(&self, antenna1: Position, antenna2: Position, with_resonance: bool) -> Vec<Position> {
-        let diff = antenna2 - antenna1;
-        if diff.x == 0 && diff.y == 0 {
-            return Vec::new();
+    fn calculate_antinodes(&self, antenna1: Position, antenna2: Position, mode: Mode) -> Vec<Position> {
+        let mut diff = antenna2 - antenna1;
+        if mode == Mode::Resonance {
+            diff = diff.reduced();
         }
 
         let mut antinodes = Vec::new();
         let mut antinode: Position;
 
-        if !with_resonance {
+        if mode == Mode::Pair {
             antinode = antenna2 + diff;
             if self.in_grid(&antinode) {
                 antinodes.push(antinode);
@@ -80,12 +136,13 @@ impl Data {
                 antinodes.push(antinode);
             }
         } else {
-            antinode = antenna2.clone();
+            // Walking from one antenna both ways also covers the points between the two antennas.
+            antinode = antenna1;
             while self.in_grid(&antinode) {
                 antinodes.push(antinode);
                 antinode = antinode + diff;
             }
-            antinode = antenna1.clone();
+            antinode = antenna1 - diff;
             while self.in_grid(&antinode) {
                 antinodes.push(antinode);
                 antinode = antinode - diff;
@@ -95,30 +152,46 @@ impl Data {
         antinodes
     }
 
-    fn calculate_all_antinodes(&self, with_resonance: bool) -> HashSet<Position> {
-        self.indexed.values()
-            .flat_map(|antennas| {
-                antennas.iter().flat_map(|antenna1| {
-                    antennas.iter().flat_map(move |antenna2| {
-                        self.calculate_antinodes(*antenna1, *antenna2, with_resonance)
-                    })
-                })
+    pub fn antinode_report(&self, mode: Mode) -> AntinodeReport {
+        let by_frequency = self.indexed.iter()
+            .map(|(frequency, antennas)| {
+                let antinodes = pairs(antennas)
+                    .flat_map(|(antenna1, antenna2)| self.calculate_antinodes(antenna1, antenna2, mode))
+                    .collect::<HashSet<Position>>();
+                (*frequency, antinodes)
             })
-            .collect::<HashSet<Position>>()
+            .collect();
+        AntinodeReport { by_frequency }
+    }
+
+    /// The map with `#` on every antinode, drawn over the antennas.
+    pub fn render(&self, antinodes: &HashSet<Position>) -> String {
+        let mut grid = vec![vec!['.'; self.width as usize]; self.height as usize];
+        self.indexed.iter().for_each(|(frequency, antennas)| {
+            antennas.iter().for_each(|pos| grid[pos.y as usize][pos.x as usize] = *frequency);
+        });
+        antinodes.iter().for_each(|pos| grid[pos.y as usize][pos.x as usize] = '#');
+        grid.iter()
+            .map(|row| row.iter().collect::<String>() + "\n")
+            .collect()
     }
 }
 
-pub struct Day08;
+const PART2_STRATEGIES: [&str; 2] = ["resonance", "literal"];
+
+pub struct Day08 {
+    /// The mode of part 2, [`Mode::LiteralResonance`] to read the puzzle text to the letter.
+    resonance: Mode,
+}
 
 impl DaySolution<Data, usize> for Day08 {
     fn new() -> Self {
-        Self {}
+        Self { resonance: Mode::Resonance }
     }
 
     fn day(&self) -> u16 { 8 }
 
-    fn parse_input(&mut self, example: bool) -> Data {
-        let raw_data = read_raw_data(self.day(), example);
+    fn parse_input(&mut self, raw_data: &str) -> Data {
         let raw_data = raw_data.lines().collect::<Vec<&str>>();
 
         let mut data = Data::new(raw_data[0].len() as i32, raw_data.len() as i32);
@@ -135,10 +208,38 @@ impl DaySolution<Data, usize> for Day08 {
     }
 
     fn part1(&mut self, input: &Data) -> usize {
-        input.calculate_all_antinodes(false).len()
+        input.antinode_report(Mode::Pair).all().len()
     }
 
     fn part2(&mut self, input: &Data) -> usize {
-        input.calculate_all_antinodes(true).len()
+        input.antinode_report(self.resonance).all().len()
+    }
+
+    fn render(&mut self, input: &Data) -> Option<String> {
+        let pair = input.antinode_report(Mode::Pair);
+        let resonance = input.antinode_report(self.resonance);
+        Some(format!(
+            "Part 1 antinodes:\n{}{}Part 2 antinodes:\n{}{}",
+            input.render(&pair.all()), pair.describe_overlaps(),
+            input.render(&resonance.all()), resonance.describe_overlaps(),
+        ))
+    }
+
+    fn strategies(&self, part: Part) -> &'static [&'static str] {
+        match part {
+            Part::One => &[DEFAULT_STRATEGY],
+            Part::Two => &PART2_STRATEGIES,
+        }
+    }
+
+    fn use_strategy(&mut self, part: Part, name: &str) -> bool {
+        match (part, name) {
+            (Part::One, DEFAULT_STRATEGY) => true,
+            (Part::Two, "resonance") | (Part::Two, "literal") => {
+                self.resonance = if name == "literal" { Mode::LiteralResonance } else { Mode::Resonance };
+                true
+            }
+            _ => false,
+        }
     }
 }
@@ -1,139 +1,356 @@
-use std::collections::HashSet;
-use crate::tools::read_raw_data;
-use crate::DaySolution;
-use std::usize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use crate::{DaySolution, Part};
 
-#[derive(Clone)]
-pub struct File {
-    id: usize,
-    len: usize,
-    free: usize,
-    free_blocs: Vec<usize>,
-    free_remaining: usize,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Span {
+    File { id: usize, len: usize },
+    Free { len: usize },
 }
 
-impl File {
-    pub fn generate_file_blocs(&self) -> Vec<usize> {
-        (0..self.len)
-            .map(|_| self.id)
-            .collect::<Vec<usize>>()
+impl Span {
+    fn len(&self) -> usize {
+        match self {
+            Span::File { len, .. } | Span::Free { len } => *len,
+        }
     }
 }
 
-pub type Data = Vec<File>;
-
-pub struct Day09 {}
+/// The disk layout as consecutive file and free spans, never two free spans or two spans of the
+/// same file in a row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Disk {
+    spans: Vec<Span>,
+}
 
-impl DaySolution<Data, usize> for Day09 {
-    fn new() -> Self {
-        Self {}
+impl Disk {
+    /// Reads a disk map, digits alternating between file and free lengths.
+    pub fn from_map(map: &str) -> Self {
+        let mut disk = Disk { spans: vec![] };
+        map.chars()
+            .filter(|c| c.is_ascii_digit())
+            .map(|c| c.to_digit(10).unwrap() as usize)
+            .enumerate()
+            .for_each(|(idx, len)| {
+                disk.push(if idx % 2 == 0 { Span::File { id: idx / 2, len } } else { Span::Free { len } });
+            });
+        disk
     }
 
-    fn day(&self) -> u16 {
-        9
+    /// Builds a disk of `size` blocks from `(offset, id, len)` file placements that do not overlap.
+    fn from_placements(mut placements: Vec<(usize, usize, usize)>, size: usize) -> Self {
+        placements.sort();
+        let mut disk = Disk { spans: vec![] };
+        let mut offset = 0;
+        placements.into_iter().for_each(|(start, id, len)| {
+            disk.push(Span::Free { len: start - offset });
+            disk.push(Span::File { id, len });
+            offset = start + len;
+        });
+        disk.push(Span::Free { len: size - offset });
+        disk
     }
 
-    fn parse_input(&mut self, example: bool) -> Data {
-        let raw_data = read_raw_data(self.day(), example);
+    fn push(&mut self, span: Span) {
+        if span.len() == 0 {
+            return;
+        }
+        match (self.spans.last_mut(), span) {
+            (Some(Span::Free { len }), Span::Free { len: more }) => *len += more,
+            (Some(Span::File { id, len }), Span::File { id: other, len: more }) if *id == other => *len += more,
+            _ => self.spans.push(span),
+        }
+    }
 
-        let digits = raw_data.chars()
-            .filter(|c| c.is_digit(10))
-            .map(|c| c.to_digit(10).unwrap() as usize)
-            .collect::<Vec<usize>>();
+    pub fn size(&self) -> usize {
+        self.spans.iter().map(|span| span.len()).sum()
+    }
 
-        digits.chunks(2)
-            .enumerate()
-            .map(|(id, chunk)| {
-                let free = *chunk.get(1).unwrap_or(&0);
-                File {
-                    id: id.try_into().unwrap(),
-                    len: chunk[0],
-                    free,
-                    free_blocs: vec![0; free],
-                    free_remaining: free,
+    /// Files in the order they appear, with their offset.
+    fn files(&self) -> Vec<(usize, usize, usize)> {
+        let mut offset = 0;
+        self.spans.iter()
+            .filter_map(|span| {
+                let start = offset;
+                offset += span.len();
+                match span {
+                    Span::File { id, len } => Some((start, *id, *len)),
+                    Span::Free { .. } => None,
                 }
             })
             .collect()
     }
 
-    fn part1(&mut self, input: &Data) -> usize {
-        let mut res: usize = 0;
-        let mut idx: usize = 0;
+    /// Every block of the disk, with the id of the file it holds.
+    pub fn blocks(&self) -> Vec<Option<usize>> {
+        self.spans.iter()
+            .flat_map(|span| {
+                let block = match span {
+                    Span::File { id, .. } => Some(*id),
+                    Span::Free { .. } => None,
+                };
+                std::iter::repeat_n(block, span.len())
+            })
+            .collect()
+    }
 
-        let mut small_idx = 0;
-        let mut big_idx = input.len() - 1;
+    pub fn checksum(&self) -> usize {
+        self.files().iter()
+            .map(|(start, id, len)| id * (*start..start + len).sum::<usize>())
+            .sum()
+    }
+
+    /// One character per block, `.` for free ones. Meant for small disks: ids past 35 show as `?`.
+    pub fn render(&self) -> String {
+        self.spans.iter()
+            .flat_map(|span| {
+                let c = match span {
+                    Span::File { id, .. } => char::from_digit(*id as u32, 36).unwrap_or('?'),
+                    Span::Free { .. } => '.',
+                };
+                std::iter::repeat_n(c, span.len())
+            })
+            .collect()
+    }
 
-        let mut big_element = input.get(big_idx).unwrap().generate_file_blocs();
+    pub fn compact(&self, strategy: &dyn Compaction) -> Disk {
+        strategy.compact(self)
+    }
+}
 
-        while small_idx < big_idx {
-            let small_element = input.get(small_idx).unwrap();
+pub trait Compaction {
+    fn compact(&self, disk: &Disk) -> Disk;
+}
 
-            small_element.generate_file_blocs().iter().for_each(|digit| {
-                res += idx * (*digit);
-                idx += 1;
-            });
+/// Moves blocks one at a time from the end of the disk into the leftmost free block.
+pub struct BlockWise;
+
+impl Compaction for BlockWise {
+    fn compact(&self, disk: &Disk) -> Disk {
+        let mut spans = disk.spans.clone();
+        let mut compacted = Disk { spans: vec![] };
 
-            let mut free = small_element.free;
-            while free > 0 {
-                if big_element.len() == 0 {
-                    big_idx -= 1;
-                    if small_idx == big_idx {
-                        break;
+        let mut small_idx = 0;
+        let mut big_idx = spans.len().saturating_sub(1);
+        while small_idx <= big_idx && small_idx < spans.len() {
+            match spans[small_idx] {
+                Span::File { .. } => compacted.push(spans[small_idx]),
+                Span::Free { len } => {
+                    let mut free = len;
+                    while free > 0 {
+                        while big_idx > small_idx && !matches!(spans[big_idx], Span::File { len: 1.., .. }) {
+                            big_idx -= 1;
+                        }
+                        let Span::File { id, len } = &mut spans[big_idx] else { break };
+                        let moved = free.min(*len);
+                        compacted.push(Span::File { id: *id, len: moved });
+                        *len -= moved;
+                        free -= moved;
                     }
-                    big_element = input.get(big_idx).unwrap().generate_file_blocs();
                 }
-                let digit = big_element.pop().unwrap();
-                res += idx * digit;
-                idx += 1;
-                free -= 1;
             }
-
             small_idx += 1;
         }
-        while big_element.len() > 0 {
-            let digit = big_element.pop().unwrap();
-            res += idx * digit;
-            idx += 1;
-        }
-        res
+        let free = disk.size() - compacted.size();
+        compacted.push(Span::Free { len: free });
+        compacted
     }
+}
 
-    fn part2(&mut self, input: &Data) -> usize {
-        let mut data = input.clone();
-        let mut moved = HashSet::<usize>::new();
-
-        input.iter().rev().for_each(|file| {
-            if let Some(free_file) = data.iter_mut().find(|free_file| { free_file.free_remaining >= file.len && free_file.id < file.id }) {
-                (free_file.free - free_file.free_remaining..free_file.free - free_file.free_remaining + file.len).for_each(|i| {
-                    free_file.free_blocs[i] = file.id;
-                });
-                moved.insert(file.id);
-                free_file.free_remaining -= file.len;
+/// Gaps indexed by size, each in a min-heap of their offsets. Free spans merge, so sizes are
+/// not bounded by the digits of the map.
+#[derive(Default)]
+struct Gaps(BTreeMap<usize, BinaryHeap<Reverse<usize>>>);
+
+impl Gaps {
+    fn push(&mut self, size: usize, offset: usize) {
+        self.0.entry(size).or_default().push(Reverse(offset));
+    }
+
+    fn pop(&mut self, size: usize) {
+        self.0.get_mut(&size).and_then(|heap| heap.pop());
+    }
+
+    /// The heads of every heap for files of `len` blocks, as `(offset, size)` by increasing size,
+    /// left of `before`.
+    fn candidates(&self, len: usize, before: usize) -> impl Iterator<Item=(usize, usize)> + '_ {
+        self.0.range(len..)
+            .filter_map(|(size, heap)| heap.peek().map(|Reverse(gap)| (*gap, *size)))
+            .filter(move |(gap, _)| *gap < before)
+    }
+}
+
+/// Moves files whole, highest id first, each into the gap `pick` chooses, if any.
+fn move_whole_files(disk: &Disk, pick: fn(&Gaps, usize, usize) -> Option<(usize, usize)>) -> Disk {
+    let mut gaps = Gaps::default();
+    let mut offset = 0;
+    disk.spans.iter().for_each(|span| {
+        if let Span::Free { len } = span {
+            gaps.push(*len, offset);
+        }
+        offset += span.len();
+    });
+
+    let mut files = disk.files();
+    files.sort_by_key(|(_, id, _)| Reverse(*id));
+    files.iter_mut().for_each(|(start, _, len)| {
+        if let Some((gap, size)) = pick(&gaps, *len, *start) {
+            gaps.pop(size);
+            if size > *len {
+                gaps.push(size - *len, gap + *len);
             }
-        });
+            *start = gap;
+        }
+    });
+    Disk::from_placements(files, disk.size())
+}
+
+/// Moves whole files, highest id first, into the leftmost gap large enough. A gap that is only
+/// partly filled goes back into the index for its remaining size.
+pub struct WholeFile;
+
+impl Compaction for WholeFile {
+    fn compact(&self, disk: &Disk) -> Disk {
+        move_whole_files(disk, |gaps, len, start| gaps.candidates(len, start).min())
+    }
+}
 
-        let mut res = 0_usize;
-        let mut idx = 0_usize;
-
-        data.into_iter().for_each(|file| {
-            if moved.contains(&file.id) {
-                idx += file.len;
-            } else {
-                file.generate_file_blocs().iter().for_each(|digit| {
-                    res += idx * (*digit);
-                    idx += 1;
-                });
+/// Like [`WholeFile`], but into the smallest gap large enough, the leftmost of those on a tie.
+pub struct BestFit;
+
+impl Compaction for BestFit {
+    fn compact(&self, disk: &Disk) -> Disk {
+        move_whole_files(disk, |gaps, len, start| gaps.candidates(len, start).next())
+    }
+}
+
+/// Compacts the disk block by block, as a reference for [`Compaction`]: moves the last file block
+/// into the first free block until no free block is left of a file block, or, with `whole_files`,
+/// moves each file once, highest id first, into the first run of free blocks left of it that fits.
+pub fn compact_by_brute_force(disk: &Disk, whole_files: bool) -> Vec<Option<usize>> {
+    let mut blocks = disk.blocks();
+    if !whole_files {
+        // The first free block and the last file block only ever move inwards.
+        let (mut free, mut file) = (0, blocks.len());
+        loop {
+            while free < blocks.len() && blocks[free].is_some() {
+                free += 1;
+            }
+            while file > 0 && blocks[file - 1].is_none() {
+                file -= 1;
             }
+            if free + 1 >= file {
+                return blocks;
+            }
+            blocks.swap(free, file - 1);
+        }
+    }
 
-            file.free_blocs.iter().for_each(|digit| {
-                res += idx * (*digit);
-                idx += 1;
-            });
-        });
-        res
+    let max_id = blocks.iter().flatten().max().copied();
+    for id in (0..=max_id.unwrap_or(0)).rev() {
+        let Some(start) = blocks.iter().position(|block| *block == Some(id)) else { continue };
+        let len = blocks.iter().filter(|block| **block == Some(id)).count();
+        let target = (0..start).find(|gap| blocks[*gap..*gap + len].iter().all(|block| block.is_none()));
+        if let Some(gap) = target {
+            (0..len).for_each(|i| blocks.swap(gap + i, start + i));
+        }
+    }
+    blocks
+}
+
+pub type Data = Disk;
+
+/// How the disk is compacted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Method {
+    /// [`BlockWise`], part 1 only.
+    TwoPointer,
+    /// [`WholeFile`], part 2 only.
+    GapHeaps,
+    /// Block by block with [`compact_by_brute_force`], whole files in part 2.
+    Blocks,
+}
+
+impl Method {
+    const fn name(self) -> &'static str {
+        match self {
+            Method::TwoPointer => "two-pointer",
+            Method::GapHeaps => "gap-heaps",
+            Method::Blocks => "blocks",
+        }
+    }
+
+    fn of(part: Part) -> &'static [Method] {
+        match part {
+            Part::One => &PART1_METHODS,
+            Part::Two => &PART2_METHODS,
+        }
+    }
+}
+
+const PART1_METHODS: [Method; 2] = [Method::TwoPointer, Method::Blocks];
+const PART1_STRATEGIES: [&str; 2] = [PART1_METHODS[0].name(), PART1_METHODS[1].name()];
+const PART2_METHODS: [Method; 2] = [Method::GapHeaps, Method::Blocks];
+const PART2_STRATEGIES: [&str; 2] = [PART2_METHODS[0].name(), PART2_METHODS[1].name()];
 
+pub struct Day09 {
+    methods: [Method; 2],
+}
+
+impl Day09 {
+    fn checksum(&self, disk: &Disk, part: Part) -> usize {
+        match self.methods[part.index()] {
+            Method::TwoPointer => disk.compact(&BlockWise).checksum(),
+            Method::GapHeaps => disk.compact(&WholeFile).checksum(),
+            Method::Blocks => compact_by_brute_force(disk, part == Part::Two).iter()
+                .enumerate()
+                .map(|(idx, block)| idx * block.unwrap_or(0))
+                .sum(),
+        }
     }
 }
 
-// 6361380647183
-// 6358866059387
+impl DaySolution<Data, usize> for Day09 {
+    fn new() -> Self {
+        Self { methods: [PART1_METHODS[0], PART2_METHODS[0]] }
+    }
+
+    fn day(&self) -> u16 {
+        9
+    }
+
+    fn parse_input(&mut self, raw_data: &str) -> Data {
+        Disk::from_map(raw_data)
+    }
+
+    fn part1(&mut self, input: &Data) -> usize {
+        self.checksum(input, Part::One)
+    }
+
+    fn part2(&mut self, input: &Data) -> usize {
+        self.checksum(input, Part::Two)
+    }
+
+    fn strategies(&self, part: Part) -> &'static [&'static str] {
+        match part {
+            Part::One => &PART1_STRATEGIES,
+            Part::Two => &PART2_STRATEGIES,
+        }
+    }
+
+    fn use_strategy(&mut self, part: Part, name: &str) -> bool {
+        let Some(method) = Method::of(part).iter().find(|method| method.name() == name) else { return false };
+        self.methods[part.index()] = *method;
+        true
+    }
+
+    fn render(&mut self, input: &Data) -> Option<String> {
+        Some(format!(
+            "Disk map   :: {}\nBlock-wise :: {}\nWhole-file :: {}\nBest fit   :: {}\n",
+            input.render(),
+            input.compact(&BlockWise).render(),
+            input.compact(&WholeFile).render(),
+            input.compact(&BestFit).render(),
+        ))
+    }
+}
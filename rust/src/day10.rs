@@ -1,11 +1,12 @@
 use std::collections::HashSet;
-use crate::tools::read_raw_data;
-use crate::DaySolution;
+use std::fmt::{Display, Formatter};
+use crate::parsing::char_grid;
+use crate::{DaySolution, Part};
 
-#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct Position {
-    x: i32,
-    y: i32,
+    pub x: i32,
+    pub y: i32,
 }
 
 impl Position {
@@ -14,13 +15,41 @@ impl Position {
     }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Hash)]
-pub struct TailHead {
-    tail: Position,
-    head: Position,
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// What makes a trail: it starts at height `start`, every step changes the height by exactly
+/// `step`, and it ends at height `end`. The puzzle's trails go from 0 to 9 by steps of 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrailRules {
+    pub start: i32,
+    pub end: i32,
+    pub step: i32,
 }
 
-pub struct Data(Vec<Vec<i32>>);
+impl Default for TrailRules {
+    fn default() -> Self {
+        TrailRules { start: 0, end: 9, step: 1 }
+    }
+}
+
+impl TrailRules {
+    fn next(&self, height: i32) -> i32 {
+        height + self.step
+    }
+
+    /// Whether a trail going through `height` can still reach the end.
+    fn on_the_way(&self, height: i32) -> bool {
+        let (low, high) = (self.start.min(self.end), self.start.max(self.end));
+        (low..=high).contains(&height) && (self.end - height) % self.step == 0
+    }
+}
+
+/// Heights of the map, `None` for cells no trail can go through.
+pub struct Data(Vec<Vec<Option<i32>>>);
 impl Data {
     fn width(&self) -> i32 {
         self.0[0].len() as i32
@@ -34,63 +63,209 @@ impl Data {
         pos.x >= 0 && pos.x < self.width() && pos.y >= 0 && pos.y < self.height()
     }
 
-    fn get(&self, pos: Position) -> i32 {
+    fn get(&self, pos: Position) -> Option<i32> {
         self.0[pos.y as usize][pos.x as usize]
     }
 
-    fn next_pos(&self, pos: Position) -> Vec<Position> {
-        vec![
+    fn index(&self, pos: Position) -> usize {
+        (pos.y * self.width() + pos.x) as usize
+    }
+
+    fn positions(&self) -> impl Iterator<Item=Position> + '_ {
+        (0..self.height()).flat_map(|y| (0..self.width()).map(move |x| Position::new(x, y)))
+    }
+
+    fn next_pos(&self, pos: Position) -> impl Iterator<Item=Position> + '_ {
+        [
             Position::new(pos.x + 1, pos.y),
             Position::new(pos.x - 1, pos.y),
             Position::new(pos.x, pos.y + 1),
             Position::new(pos.x, pos.y - 1),
-        ].iter()
-            .cloned()
+        ].into_iter()
             .filter(|p| self.is_valid(*p))
-            .collect::<Vec<Position>>()
     }
 
-    fn get_all_tail_heads(&self) -> Vec<TailHead> {
-        (0..self.height())
-            .flat_map(|y| (0..self.width()).map(move |x| Position::new(x, y)))
-            .filter(|pos| self.get(*pos) == 0)
-            .flat_map(|head| self.get_tails(head).iter().map(|tail| TailHead { tail: *tail, head }).collect::<Vec<TailHead>>())
-            .collect::<Vec<TailHead>>()
+    /// The cells one step further along a trail.
+    fn next_steps(&self, pos: Position, rules: &TrailRules) -> impl Iterator<Item=Position> + '_ {
+        let next = self.get(pos).map(|height| rules.next(height));
+        self.next_pos(pos).filter(move |p| next.is_some() && self.get(*p) == next)
+    }
+
+    /// Per cell, how many trails go from it to a trail end (its rating). Cells are visited from
+    /// the trail ends back, so each cell only has to add up what its neighbours one step further
+    /// already know.
+    fn ratings(&self, rules: &TrailRules) -> Vec<usize> {
+        let mut ratings = vec![0; (self.width() * self.height()) as usize];
+        // One bucket per height from the end back, so that ordering the cells stays linear.
+        // Steps away from the end, none when the steps lead away from it.
+        let steps_left = |height: i32| usize::try_from((rules.end - height) / rules.step).ok();
+        let mut by_height = vec![vec![]; steps_left(rules.start).map_or(0, |steps| steps + 1)];
+        self.positions()
+            .filter(|pos| self.get(*pos).is_some_and(|height| rules.on_the_way(height)))
+            .for_each(|pos| {
+                if let Some(bucket) = steps_left(self.get(pos).unwrap()).and_then(|steps| by_height.get_mut(steps)) {
+                    bucket.push(pos);
+                }
+            });
+        by_height.iter().flatten().for_each(|pos| {
+            let idx = self.index(*pos);
+            ratings[idx] = if self.get(*pos) == Some(rules.end) {
+                1
+            } else {
+                self.next_steps(*pos, rules).map(|next| ratings[self.index(next)]).sum()
+            };
+        });
+        ratings
+    }
+
+    /// How many trail ends the trails from `head` reach (its score). A trail is as long as the
+    /// rules say whatever the size of the map, so the walk only ever sees the cells around
+    /// `head`. `seen` keeps, per cell, the `mark` of the last walk through it, so that it never
+    /// has to be cleared.
+    fn score(&self, head: Position, rules: &TrailRules, seen: &mut [usize], mark: usize) -> usize {
+        let mut ends = 0;
+        let mut stack = vec![head];
+        seen[self.index(head)] = mark;
+        while let Some(pos) = stack.pop() {
+            if self.get(pos) == Some(rules.end) {
+                ends += 1;
+                continue;
+            }
+            self.next_steps(pos, rules).for_each(|next| {
+                let idx = self.index(next);
+                if seen[idx] != mark {
+                    seen[idx] = mark;
+                    stack.push(next);
+                }
+            });
+        }
+        ends
+    }
+
+    pub fn trail_heads(&self, rules: &TrailRules) -> impl Iterator<Item=Position> + '_ {
+        let start = rules.start;
+        self.positions().filter(move |pos| self.get(*pos) == Some(start))
+    }
+
+    /// Every trail from `head`, each as the positions it goes through.
+    pub fn trails_from(&self, head: Position, rules: &TrailRules) -> Vec<Vec<Position>> {
+        let mut trails = vec![];
+        if self.get(head) == Some(rules.start) {
+            self.extend_trail(&mut vec![head], rules, &mut trails);
+        }
+        trails
+    }
+
+    fn extend_trail(&self, trail: &mut Vec<Position>, rules: &TrailRules, trails: &mut Vec<Vec<Position>>) {
+        let last = *trail.last().unwrap();
+        if self.get(last) == Some(rules.end) {
+            trails.push(trail.clone());
+            return;
+        }
+        self.next_steps(last, rules).for_each(|next| {
+            trail.push(next);
+            self.extend_trail(trail, rules, trails);
+            trail.pop();
+        });
     }
 
-    fn get_tails(&self, head: Position) -> Vec<Position> {
-        let current_value = self.get(head);
-        if current_value == 9 {
-            return vec![head];
+    /// The map with only the cells of the given trails.
+    pub fn render(&self, trails: &[Vec<Position>]) -> String {
+        let mut grid = vec![vec!['.'; self.width() as usize]; self.height() as usize];
+        trails.iter().flatten().for_each(|pos| {
+            grid[pos.y as usize][pos.x as usize] = self.get(*pos)
+                .and_then(|height| char::from_digit(height as u32, 36))
+                .unwrap_or('?');
+        });
+        grid.iter()
+            .map(|row| row.iter().collect::<String>() + "\n")
+            .collect()
+    }
+}
+
+/// How trails are counted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Counting {
+    /// Ratings from the trail ends back, scores by walking from each head.
+    Dp,
+    /// Every trail followed from its head with [`Data::trails_from`].
+    Dfs,
+}
+
+impl Counting {
+    const fn name(self) -> &'static str {
+        match self {
+            Counting::Dp => "dp",
+            Counting::Dfs => "dfs",
         }
-        self.next_pos(head)
-            .iter()
-            .filter(|pos| self.get(**pos) == current_value + 1)
-            .flat_map(|pos| self.get_tails(*pos))
-            .collect::<Vec<Position>>()
     }
 }
 
-pub struct Day10;
+const COUNTINGS: [Counting; 2] = [Counting::Dp, Counting::Dfs];
+const STRATEGIES: [&str; 2] = [COUNTINGS[0].name(), COUNTINGS[1].name()];
+
+pub struct Day10 {
+    rules: TrailRules,
+    countings: [Counting; 2],
+}
+
+impl Day10 {
+    /// Scores and rates trails that follow `rules` instead of the puzzle's.
+    pub fn with_rules(rules: TrailRules) -> Self {
+        Self {
+            rules,
+            countings: [Counting::Dp; 2],
+        }
+    }
+
+    /// The sum over trail heads of the summits they reach (part 1) or of their trails (part 2).
+    fn sum(&self, input: &Data, part: Part) -> usize {
+        assert_ne!(self.rules.step, 0, "a trail has to change height at every step");
+        match (self.countings[part.index()], part) {
+            (Counting::Dfs, _) => input.trail_heads(&self.rules)
+                .map(|head| {
+                    let trails = input.trails_from(head, &self.rules);
+                    match part {
+                        Part::One => trails.iter().map(|trail| trail.last()).collect::<HashSet<_>>().len(),
+                        Part::Two => trails.len(),
+                    }
+                })
+                .sum(),
+            (Counting::Dp, Part::One) => {
+                let mut seen = vec![usize::MAX; (input.width() * input.height()) as usize];
+                input.trail_heads(&self.rules)
+                    .enumerate()
+                    .map(|(mark, head)| input.score(head, &self.rules, &mut seen, mark))
+                    .sum()
+            }
+            (Counting::Dp, Part::Two) => {
+                let ratings = input.ratings(&self.rules);
+                input.trail_heads(&self.rules).map(|head| ratings[input.index(head)]).sum()
+            }
+        }
+    }
+}
 
 impl DaySolution<Data, i32> for Day10 {
     fn new() -> Self {
-        Self {}
+        Self::with_rules(TrailRules::default())
     }
 
     fn day(&self) -> u16 {
         10
     }
 
-    fn parse_input(&mut self, example: bool) -> Data {
-        let raw_data = read_raw_data(self.day(), example);
-
+    /// Digits are heights, `.` marks a blank cell.
+    fn parse_input(&mut self, raw_data: &str) -> Data {
         Data(
-            raw_data
-                .lines()
-                .map(|line| {
-                    line.chars()
-                        .map(|c| c.to_digit(10).unwrap() as i32)
+            char_grid(raw_data)
+                .into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(|c| match c {
+                            '.' => None,
+                            _ => Some(c.to_digit(10).expect("Invalid height") as i32),
+                        })
                         .collect()
                 })
                 .collect(),
@@ -98,10 +273,26 @@ impl DaySolution<Data, i32> for Day10 {
     }
 
     fn part1(&mut self, input: &Data) -> i32 {
-        input.get_all_tail_heads().iter().cloned().collect::<HashSet<TailHead>>().len() as i32
+        self.sum(input, Part::One) as i32
     }
 
     fn part2(&mut self, input: &Data) -> i32 {
-        input.get_all_tail_heads().len() as i32
+        self.sum(input, Part::Two) as i32
+    }
+
+    fn render(&mut self, input: &Data) -> Option<String> {
+        let head = input.trail_heads(&self.rules).next()?;
+        let trails = input.trails_from(head, &self.rules);
+        Some(format!("{} trails from {}:\n{}", trails.len(), head, input.render(&trails)))
+    }
+
+    fn strategies(&self, _part: Part) -> &'static [&'static str] {
+        &STRATEGIES
+    }
+
+    fn use_strategy(&mut self, part: Part, name: &str) -> bool {
+        let Some(counting) = COUNTINGS.into_iter().find(|counting| counting.name() == name) else { return false };
+        self.countings[part.index()] = counting;
+        true
     }
 }
@@ -1,74 +1,229 @@
-use crate::tools::read_raw_data;
-use crate::DaySolution;
+use crate::{DaySolution, Part};
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// What a stone turns into after one blink, `None` if its value overflows.
+fn evolve(stone: u64) -> Option<(u64, Option<u64>)> {
+    if stone == 0 {
+        return Some((1, None));
+    }
+    let digits = stone.ilog10() + 1;
+    if digits.is_multiple_of(2) {
+        let half = 10_u64.pow(digits / 2);
+        return Some((stone / half, Some(stone % half)));
+    }
+    stone.checked_mul(2024).map(|stone| (stone, None))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlinkError {
+    /// The number of stones no longer fits in a `u128`.
+    CountOverflow { blink: usize },
+    /// A stone's value no longer fits in a `u64`.
+    StoneOverflow { blink: usize, stone: u64 },
+}
+
+impl Display for BlinkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlinkError::CountOverflow { blink } => write!(f, "stone count overflows at blink {}", blink),
+            BlinkError::StoneOverflow { blink, stone } => write!(f, "stone {} overflows at blink {}", stone, blink),
+        }
+    }
+}
+
+impl std::error::Error for BlinkError {}
+
+pub struct BlinkReport {
+    /// Number of stones after the last blink.
+    pub total: u128,
+    /// Number of distinct stone values, before the first blink then after each one.
+    pub distinct: Vec<usize>,
+}
+
+/// Stones with the same value evolve the same way, so only a count per value is kept from one
+/// blink to the next. The map stays small (a few thousand values) whatever the blink count.
+pub fn simulate(stones: &[u64], blinks: usize) -> Result<BlinkReport, BlinkError> {
+    let mut counts = HashMap::<u64, u128>::new();
+    stones.iter().for_each(|stone| *counts.entry(*stone).or_default() += 1);
+    let mut distinct = vec![counts.len()];
+
+    for blink in 1..=blinks {
+        let mut next = HashMap::<u64, u128>::with_capacity(counts.len() * 2);
+        for (stone, count) in counts {
+            let (left, right) = evolve(stone).ok_or(BlinkError::StoneOverflow { blink, stone })?;
+            for new_stone in [Some(left), right].into_iter().flatten() {
+                let entry = next.entry(new_stone).or_default();
+                *entry = entry.checked_add(count).ok_or(BlinkError::CountOverflow { blink })?;
+            }
+        }
+        counts = next;
+        distinct.push(counts.len());
+    }
+
+    let total = counts.values()
+        .try_fold(0_u128, |acc, count| acc.checked_add(*count))
+        .ok_or(BlinkError::CountOverflow { blink: blinks })?;
+    Ok(BlinkReport { total, distinct })
+}
+
+/// Every stone after `blinks` blinks, applying the rules to their decimal text. Only usable for
+/// a few blinks: the row grows about 1.5 times per blink.
+pub fn blink_by_brute_force(stones: &[u64], blinks: usize) -> Vec<u64> {
+    let mut row = stones.to_vec();
+    for _ in 0..blinks {
+        row = row.iter()
+            .flat_map(|stone| {
+                let text = stone.to_string();
+                if *stone == 0 {
+                    vec![1]
+                } else if text.len() % 2 == 0 {
+                    let (left, right) = text.split_at(text.len() / 2);
+                    vec![left.parse().unwrap(), right.parse().unwrap()]
+                } else {
+                    vec![stone * 2024]
+                }
+            })
+            .collect();
+    }
+    row
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Engine {
+    /// Recursion on each stone, memoized by `(stone, blinks left)`.
+    Memo,
+    /// Counts per stone value, see [`simulate`].
+    CountMap,
+    /// Every stone kept in a row, see [`blink_by_brute_force`]. Part 1 only.
+    Literal,
+}
+
+impl Engine {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Engine::Memo => "memo",
+            Engine::CountMap => "count-map",
+            Engine::Literal => "literal",
+        }
+    }
+}
+
+const fn names<const N: usize>(engines: [Engine; N]) -> [&'static str; N] {
+    let mut names = [""; N];
+    let mut idx = 0;
+    while idx < N {
+        names[idx] = engines[idx].name();
+        idx += 1;
+    }
+    names
+}
+
+const PART1_ENGINES: [Engine; 3] = [Engine::CountMap, Engine::Memo, Engine::Literal];
+const PART1_STRATEGIES: [&str; 3] = names(PART1_ENGINES);
+const PART2_ENGINES: [Engine; 2] = [Engine::CountMap, Engine::Memo];
+const PART2_STRATEGIES: [&str; 2] = names(PART2_ENGINES);
 
 pub struct Day11 {
-    cache: HashMap<(usize, usize), usize>,
+    engines: [Engine; 2],
+    cache: HashMap<(u64, usize), u128>,
 }
 
 impl Day11 {
-    fn blink(&mut self, stone: usize, step: usize) -> usize {
+    /// Uses `engine` for both parts, or only for part 1 if part 2 cannot use it.
+    pub fn with_engine(engine: Engine) -> Self {
+        let part2 = if PART2_ENGINES.contains(&engine) { engine } else { Engine::CountMap };
+        Day11 {
+            engines: [engine, part2],
+            cache: HashMap::new(),
+        }
+    }
+
+    /// How many stones `stone` becomes after `step` more blinks, out of `blinks` in all.
+    fn blink(&mut self, stone: u64, step: usize, blinks: usize) -> Result<u128, BlinkError> {
         let id = (stone, step);
 
         if step == 0 {
-            return 1;
+            return Ok(1);
         }
 
         if let Some(res) = self.cache.get(&id) {
-            return *res;
+            return Ok(*res);
         }
 
-        if stone == 0 {
-            let res = self.blink(1, step - 1);
-            self.cache.insert(id, res);
-            return res;
-        }
+        let blink = blinks - step + 1;
+        let res = match evolve(stone).ok_or(BlinkError::StoneOverflow { blink, stone })? {
+            (left, None) => self.blink(left, step - 1, blinks)?,
+            (left, Some(right)) => self.blink(left, step - 1, blinks)?
+                .checked_add(self.blink(right, step - 1, blinks)?)
+                .ok_or(BlinkError::CountOverflow { blink })?,
+        };
+        self.cache.insert(id, res);
+        Ok(res)
+    }
 
-        let stone_str = format!("{}", stone);
-        if stone_str.len() % 2 == 0 {
-            let half = stone_str.len()/2;
-            let res =
-                self.blink(stone_str[0..half].parse::<usize>().unwrap(), step - 1)
-                    + self.blink(stone_str[half..].parse::<usize>().unwrap(), step - 1);
-            self.cache.insert(id, res);
-            return res;
+    /// The number of stones after `blinks` blinks. The memo and count-map engines report an
+    /// overflow the same way, the literal one is only meant for a few blinks.
+    pub fn count(&mut self, engine: Engine, stones: &[u64], blinks: usize) -> Result<u128, BlinkError> {
+        match engine {
+            Engine::Memo => stones.iter().try_fold(0_u128, |total, stone| {
+                total.checked_add(self.blink(*stone, blinks, blinks)?).ok_or(BlinkError::CountOverflow { blink: blinks })
+            }),
+            Engine::CountMap => simulate(stones, blinks).map(|report| report.total),
+            Engine::Literal => Ok(blink_by_brute_force(stones, blinks).len() as u128),
         }
-
-        let res = self.blink(stone * 2024, step - 1);
-        self.cache.insert(id, res);
-        res
     }
 }
 
-impl DaySolution<Vec<usize>, usize> for Day11 {
+impl DaySolution<Vec<u64>, u128> for Day11 {
     fn new() -> Self {
-        Self {
-            cache: HashMap::new(),
-        }
+        Day11::with_engine(Engine::CountMap)
     }
 
     fn day(&self) -> u16 {
         11
     }
 
-    fn parse_input(&mut self, example: bool) -> Vec<usize> {
-        let raw_data = read_raw_data(self.day(), example);
+    fn parse_input(&mut self, raw_data: &str) -> Vec<u64> {
         raw_data
             .lines()
             .flat_map(|line| {
                 line.split_whitespace()
                     .filter(|p| !p.is_empty())
-                    .map(|s| s.parse::<usize>().unwrap())
-                    .collect::<Vec<usize>>()
+                    .map(|s| s.parse::<u64>().unwrap())
+                    .collect::<Vec<u64>>()
             })
-            .collect::<Vec<usize>>()
+            .collect::<Vec<u64>>()
+    }
+
+    fn part1(&mut self, input: &Vec<u64>) -> u128 {
+        self.count(self.engines[0], input, 25).unwrap()
+    }
+
+    fn part2(&mut self, input: &Vec<u64>) -> u128 {
+        self.count(self.engines[1], input, 75).unwrap()
     }
 
-    fn part1(&mut self, input: &Vec<usize>) -> usize {
-        input.iter().map(|stone| self.blink(stone.clone(), 25)).sum()
+    fn render(&mut self, input: &Vec<u64>) -> Option<String> {
+        let report = simulate(input, 75).ok()?;
+        let distinct = report.distinct.iter().map(|d| d.to_string()).collect::<Vec<String>>();
+        Some(format!("Distinct stones per blink :: {}\n", distinct.join(" ")))
+    }
+
+    fn strategies(&self, part: Part) -> &'static [&'static str] {
+        match part {
+            Part::One => &PART1_STRATEGIES,
+            Part::Two => &PART2_STRATEGIES,
+        }
     }
 
-    fn part2(&mut self, input: &Vec<usize>) -> usize {
-        input.iter().map(|stone| self.blink(stone.clone(), 75)).sum()
+    fn use_strategy(&mut self, part: Part, name: &str) -> bool {
+        let engines: &[Engine] = match part {
+            Part::One => &PART1_ENGINES,
+            Part::Two => &PART2_ENGINES,
+        };
+        let Some(idx) = self.strategies(part).iter().position(|strategy| *strategy == name) else { return false };
+        self.engines[part.index()] = engines[idx];
+        true
     }
 }
@@ -0,0 +1,258 @@
+//! Random puzzle inputs, in the same text format as the real ones, for stress tests and for
+//! benchmarks beyond the official sizes. The same seed and knobs always give the same input.
+
+use std::collections::HashSet;
+use crate::day06::Day06;
+use crate::DaySolution;
+
+/// xorshift64: small, fast and reproducible, which is all the generators need.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // A zero state would only ever produce zeros.
+        let mut rng = Rng(seed ^ 0x9e37_79b9_7f4a_7c15);
+        rng.next_u64();
+        rng
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A number in `low..=high`.
+    pub fn between(&mut self, low: usize, high: usize) -> usize {
+        low + self.below(high - low + 1)
+    }
+
+    /// True with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64) < p
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// How big a generated input is. Each day reads the knobs that make sense for it:
+///
+/// - `count`: list pairs (01), reports (02), instructions (03), updates (05), equations (07),
+///   disk map digits (09), trails planted (10), stones (11);
+/// - `width` and `height`: grid dimensions (04, 06, 08, 10), pages in the rules (05, `width`),
+///   numbers per equation at most (07, `width`);
+/// - `density`: obstacles (06), antennas (08), noise between instructions (03), unsafe reports
+///   (02), unsolvable equations (07).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Knobs {
+    pub seed: u64,
+    pub count: usize,
+    pub width: usize,
+    pub height: usize,
+    pub density: f64,
+}
+
+impl Knobs {
+    /// Roughly the size of the official input of `day`.
+    pub fn official(day: u16) -> Knobs {
+        let (count, width, height, density) = match day {
+            1 => (1000, 0, 0, 0.0),
+            2 => (1000, 0, 0, 0.5),
+            3 => (700, 0, 0, 0.5),
+            4 => (0, 140, 140, 0.0),
+            5 => (200, 49, 0, 0.0),
+            6 => (0, 130, 130, 0.01),
+            7 => (850, 12, 0, 0.5),
+            8 => (0, 50, 50, 0.08),
+            9 => (19999, 0, 0, 0.0),
+            10 => (300, 50, 50, 0.0),
+            11 => (8, 0, 0, 0.0),
+            _ => (0, 0, 0, 0.0),
+        };
+        Knobs { seed: 0, count, width, height, density }
+    }
+}
+
+/// A random input for `day`, `None` for a day without a generator.
+pub fn generate(day: u16, knobs: &Knobs) -> Option<String> {
+    let rng = &mut Rng::new(knobs.seed);
+    let input = match day {
+        1 => day01(rng, knobs),
+        2 => day02(rng, knobs),
+        3 => day03(rng, knobs),
+        4 => day04(rng, knobs),
+        5 => day05(rng, knobs),
+        6 => day06(rng, knobs),
+        7 => day07(rng, knobs),
+        8 => day08(rng, knobs),
+        9 => day09(rng, knobs),
+        10 => day10(rng, knobs),
+        11 => day11(rng, knobs),
+        _ => return None,
+    };
+    Some(input)
+}
+
+fn lines(lines: impl Iterator<Item=String>) -> String {
+    lines.map(|line| line + "\n").collect()
+}
+
+fn day01(rng: &mut Rng, knobs: &Knobs) -> String {
+    // Small values make the right list repeat some of the left one, as part 2 expects.
+    lines((0..knobs.count).map(|_| format!("{}   {}", rng.between(10000, 10099), rng.between(10000, 10099))))
+}
+
+fn day02(rng: &mut Rng, knobs: &Knobs) -> String {
+    lines((0..knobs.count).map(|_| {
+        let direction = if rng.chance(0.5) { 1 } else { -1 };
+        let len = rng.between(5, 8);
+        // An unsafe report gets one step of the wrong size or the wrong way.
+        let bad = rng.chance(knobs.density).then(|| rng.below(len - 1));
+        let mut level = rng.between(20, 80) as i32;
+        let mut levels = vec![level];
+        for step in 0..len - 1 {
+            level += if bad == Some(step) { rng.between(0, 5) as i32 * -direction } else { rng.between(1, 3) as i32 * direction };
+            levels.push(level);
+        }
+        levels.iter().map(|level| level.to_string()).collect::<Vec<String>>().join(" ")
+    }))
+}
+
+fn day03(rng: &mut Rng, knobs: &Knobs) -> String {
+    const NOISE: [&str; 10] = ["mul(1, 2)", "mul[3,7]", "mul(4*", "do_not_", "don't", "?(", ")", "%&", "from()", "mul(1234,5)"];
+    let mut memory = String::new();
+    for _ in 0..knobs.count {
+        while rng.chance(knobs.density) {
+            memory.push_str(rng.pick(&NOISE));
+        }
+        match rng.below(10) {
+            0 => memory.push_str("do()"),
+            1 => memory.push_str("don't()"),
+            _ => memory.push_str(&format!("mul({},{})", rng.between(1, 999), rng.between(1, 999))),
+        }
+    }
+    memory + "\n"
+}
+
+fn day04(rng: &mut Rng, knobs: &Knobs) -> String {
+    lines((0..knobs.height).map(|_| (0..knobs.width).map(|_| rng.pick(&['X', 'M', 'A', 'S'])).collect()))
+}
+
+/// Rules for every pair among `width` pages in a hidden order, then updates of an odd number of
+/// distinct pages.
+fn day05(rng: &mut Rng, knobs: &Knobs) -> String {
+    let mut order = (10..10 + knobs.width.max(2)).collect::<Vec<usize>>();
+    rng.shuffle(&mut order);
+    let mut rules = vec![];
+    for i in 0..order.len() {
+        for j in i + 1..order.len() {
+            rules.push(format!("{}|{}", order[i], order[j]));
+        }
+    }
+    rng.shuffle(&mut rules);
+
+    let updates = (0..knobs.count).map(|_| {
+        let mut update = order.clone();
+        rng.shuffle(&mut update);
+        update.truncate(rng.between(0, (order.len() - 1) / 2) * 2 + 1);
+        update.iter().map(|page| page.to_string()).collect::<Vec<String>>().join(",")
+    });
+    format!("{}\n{}", lines(rules.into_iter()), lines(updates))
+}
+
+/// Obstacles at random and the guard facing up, retried until the guard walks off the map.
+fn day06(rng: &mut Rng, knobs: &Knobs) -> String {
+    let (width, height) = (knobs.width.max(1), knobs.height.max(1));
+    loop {
+        let mut grid = (0..height)
+            .map(|_| (0..width).map(|_| if rng.chance(knobs.density) { '#' } else { '.' }).collect())
+            .collect::<Vec<Vec<char>>>();
+        grid[rng.below(height)][rng.below(width)] = '^';
+        let map = lines(grid.into_iter().map(|row| row.into_iter().collect()));
+        // Without a loop the guard is at most once in each cell facing each way.
+        if Day06::new().parse_input(&map).walk().nth(4 * width * height).is_none() {
+            return map;
+        }
+    }
+}
+
+/// Each equation's target comes from random operators, falling back to `+` where the others
+/// would overflow, or is off by one when meant to be unsolvable.
+fn day07(rng: &mut Rng, knobs: &Knobs) -> String {
+    lines((0..knobs.count).map(|_| {
+        let numbers = (0..rng.between(2, knobs.width.max(2))).map(|_| rng.between(1, 999) as i128).collect::<Vec<i128>>();
+        let target = numbers[1..].iter().fold(numbers[0], |acc, n| {
+            let result = match rng.below(3) {
+                0 => None,
+                1 => acc.checked_mul(*n),
+                _ => format!("{}{}", acc, n).parse().ok(),
+            };
+            result.unwrap_or(acc + n)
+        });
+        let target = if rng.chance(knobs.density) { target + 1 } else { target };
+        let numbers = numbers.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(" ");
+        format!("{}: {}", target, numbers)
+    }))
+}
+
+fn day08(rng: &mut Rng, knobs: &Knobs) -> String {
+    let frequencies = ('0'..='9').chain('a'..='z').chain('A'..='Z').collect::<Vec<char>>();
+    lines((0..knobs.height).map(|_| {
+        (0..knobs.width).map(|_| if rng.chance(knobs.density) { rng.pick(&frequencies) } else { '.' }).collect()
+    }))
+}
+
+/// File lengths from 1 to 9, free lengths from 0 to 9, ending on a file.
+fn day09(rng: &mut Rng, knobs: &Knobs) -> String {
+    let digits = knobs.count.max(1) | 1;
+    let map = (0..digits)
+        .map(|i| char::from_digit(if i % 2 == 0 { rng.between(1, 9) } else { rng.below(10) } as u32, 10).unwrap())
+        .collect::<String>();
+    map + "\n"
+}
+
+/// Random heights with `count` trails from 0 to 9 planted as random walks over them.
+fn day10(rng: &mut Rng, knobs: &Knobs) -> String {
+    let (width, height) = (knobs.width.max(1), knobs.height.max(1));
+    let mut grid = (0..height).map(|_| (0..width).map(|_| rng.below(10) as u8).collect()).collect::<Vec<Vec<u8>>>();
+    for _ in 0..knobs.count {
+        let (mut x, mut y) = (rng.below(width), rng.below(height));
+        let mut trail = HashSet::new();
+        for h in 0..10 {
+            grid[y][x] = h;
+            trail.insert((x, y));
+            let next = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)]
+                .into_iter()
+                .filter(|(x, y)| *x < width && *y < height && !trail.contains(&(*x, *y)))
+                .collect::<Vec<(usize, usize)>>();
+            if next.is_empty() {
+                break;
+            }
+            (x, y) = rng.pick(&next);
+        }
+    }
+    lines(grid.into_iter().map(|row| row.into_iter().map(|h| char::from(b'0' + h)).collect()))
+}
+
+fn day11(rng: &mut Rng, knobs: &Knobs) -> String {
+    let stones = (0..knobs.count).map(|_| {
+        let digits = rng.between(1, 7) as u32;
+        rng.below(10_usize.pow(digits)).to_string()
+    });
+    stones.collect::<Vec<String>>().join(" ") + "\n"
+}
@@ -0,0 +1,257 @@
+use std::any::Any;
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::panic;
+use std::sync::Once;
+
+pub mod tools;
+pub mod parsing;
+pub mod parallel;
+pub mod cancel;
+pub mod memory;
+pub mod generate;
+pub mod scaling;
+pub mod minimize;
+pub mod runner;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+
+pub trait DaySolution<I, O> {
+    fn new() -> Self where Self: Sized;
+    fn day(&self) -> u16;
+    fn parse_input(&mut self, raw_data: &str) -> I;
+    fn part1(&mut self, input: &I) -> O;
+    fn part2(&mut self, input: &I) -> O;
+
+    /// A picture of the example, appended to the result file.
+    fn render(&mut self, _input: &I) -> Option<String> {
+        None
+    }
+
+    /// The names of the ways this day can solve `part`, the one it uses by default first.
+    fn strategies(&self, _part: Part) -> &'static [&'static str] {
+        &[DEFAULT_STRATEGY]
+    }
+
+    /// Solves `part` the way `name` says from now on, `false` for a name not in
+    /// [`Self::strategies`].
+    fn use_strategy(&mut self, part: Part, name: &str) -> bool {
+        self.strategies(part).contains(&name)
+    }
+}
+
+/// The only strategy of a day with a single way of solving a part.
+pub const DEFAULT_STRATEGY: &str = "default";
+
+pub const YEAR: &str = "2024";
+pub const LANG: &str = "[rust]";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    /// 0 for part 1, 1 for part 2, to keep something per part in an array.
+    pub fn index(self) -> usize {
+        match self {
+            Part::One => 0,
+            Part::Two => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = Error;
+
+    fn try_from(part: u8) -> Result<Self, Error> {
+        match part {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(Error::UnknownPart(part)),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "Part 1"),
+            Part::Two => write!(f, "Part 2"),
+        }
+    }
+}
+
+/// An answer as it would be typed on the puzzle page.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Answer(String);
+
+impl Answer {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad(&self.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    UnsupportedYear(u16),
+    UnknownDay(u16),
+    UnknownPart(u8),
+    /// The day gave up on the input, with what it said when it did.
+    Parse { day: u16, message: String },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnsupportedYear(year) => write!(f, "year {} is not supported, only {}", year, YEAR),
+            Error::UnknownDay(day) => write!(f, "day {} is not solved", day),
+            Error::UnknownPart(part) => write!(f, "part {} does not exist", part),
+            Error::Parse { day, message } => write!(f, "day {} cannot solve this input: {}", day, message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A [`DaySolution`] with its input and answer types erased, so all days fit in one list.
+pub trait Puzzle {
+    fn day(&self) -> u16;
+    fn parse_input(&mut self, raw_data: &str) -> Box<dyn Any>;
+    fn solve(&mut self, input: &dyn Any, part: Part) -> Answer;
+    fn render(&mut self, input: &dyn Any) -> Option<String>;
+    fn strategies(&self, part: Part) -> &'static [&'static str];
+    fn use_strategy(&mut self, part: Part, name: &str) -> bool;
+}
+
+struct Erased<S, I, O> {
+    solution: S,
+    types: PhantomData<fn(I) -> O>,
+}
+
+impl<S: DaySolution<I, O>, I: 'static, O: Display> Erased<S, I, O> {
+    fn input<'a>(&self, input: &'a dyn Any) -> &'a I {
+        input.downcast_ref::<I>().expect("input parsed by another day")
+    }
+}
+
+impl<S: DaySolution<I, O>, I: 'static, O: Display> Puzzle for Erased<S, I, O> {
+    fn day(&self) -> u16 {
+        self.solution.day()
+    }
+
+    fn parse_input(&mut self, raw_data: &str) -> Box<dyn Any> {
+        Box::new(self.solution.parse_input(raw_data))
+    }
+
+    fn solve(&mut self, input: &dyn Any, part: Part) -> Answer {
+        let input = self.input(input);
+        let answer = match part {
+            Part::One => self.solution.part1(input),
+            Part::Two => self.solution.part2(input),
+        };
+        Answer(answer.to_string())
+    }
+
+    fn render(&mut self, input: &dyn Any) -> Option<String> {
+        let input = self.input(input);
+        self.solution.render(input)
+    }
+
+    fn strategies(&self, part: Part) -> &'static [&'static str] {
+        self.solution.strategies(part)
+    }
+
+    fn use_strategy(&mut self, part: Part, name: &str) -> bool {
+        self.solution.use_strategy(part, name)
+    }
+}
+
+/// A new `S` as a [`Puzzle`], for the registry or for solutions outside of it.
+pub fn erase<S: DaySolution<I, O> + 'static, I: 'static, O: Display + 'static>() -> Box<dyn Puzzle> {
+    Box::new(Erased {
+        solution: S::new(),
+        types: PhantomData,
+    })
+}
+
+/// Every solved day, in order.
+pub fn puzzles() -> Vec<Box<dyn Puzzle>> {
+    vec![
+        erase::<day01::Day01, _, _>(),
+        erase::<day02::Day02, _, _>(),
+        erase::<day03::Day03, _, _>(),
+        erase::<day04::Day04, _, _>(),
+        erase::<day05::Day05, _, _>(),
+        erase::<day06::Day06, _, _>(),
+        erase::<day07::Day07, _, _>(),
+        erase::<day08::Day08, _, _>(),
+        erase::<day09::Day09, _, _>(),
+        erase::<day10::Day10, _, _>(),
+        erase::<day11::Day11, _, _>(),
+    ]
+}
+
+pub fn puzzle(day: u16) -> Option<Box<dyn Puzzle>> {
+    puzzles().into_iter().find(|puzzle| puzzle.day() == day)
+}
+
+thread_local! {
+    /// Set while [`solve`] runs a day on this thread, so that its panics are not printed.
+    static QUIET_PANICS: Cell<bool> = const { Cell::new(false) };
+}
+
+static QUIET_HOOK: Once = Once::new();
+
+/// Runs `f`, turning a panic into [`Error::Parse`] without printing it. The hook that stays
+/// quiet is installed once and defers to the previous one on other threads, so concurrent calls
+/// and panics elsewhere are unaffected.
+fn catch_parse_panic<R>(day: u16, f: impl FnOnce() -> R) -> Result<R, Error> {
+    QUIET_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET_PANICS.with(Cell::get) {
+                previous(info)
+            }
+        }));
+    });
+    let quiet = QUIET_PANICS.with(|quiet| quiet.replace(true));
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(f));
+    QUIET_PANICS.with(|current| current.set(quiet));
+    result.map_err(|payload| {
+        let message = payload.downcast_ref::<&str>().map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        Error::Parse { day, message }
+    })
+}
+
+/// Parses `input` and solves one part of a day. A day that panics on a malformed input gives
+/// [`Error::Parse`].
+pub fn solve(year: u16, day: u16, part: Part, input: &str) -> Result<Answer, Error> {
+    if year.to_string() != YEAR {
+        return Err(Error::UnsupportedYear(year));
+    }
+    let mut puzzle = puzzle(day).ok_or(Error::UnknownDay(day))?;
+    catch_parse_panic(day, || {
+        let input = puzzle.parse_input(input);
+        puzzle.solve(input.as_ref(), part)
+    })
+}
@@ -1,99 +1,166 @@
 use std::env;
-use std::fmt::Display;
-use std::ops::Range;
-use std::time::Instant;
+use std::fs;
+use std::process;
+use std::time::Duration;
+use rust::generate::{generate, Knobs};
+use rust::minimize::{minimize, Predicate};
+use rust::runner::{self, Options};
+use rust::{memory, parallel, scaling, Part};
 
-mod tools;
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
+/// `generate DAY [--seed N] [--count N] [--width N] [--height N] [--density P] [--out FILE]`
+///
+/// Knobs not given keep the size of the official input. Writes to
+/// `../data/dayNN-gen-sSEED.input` unless `--out` says otherwise, `-` being stdout.
+fn generate_command(args: &[String]) {
+    let day = args[0].parse::<u16>().unwrap();
+    let mut knobs = Knobs::official(day);
+    let mut out = None;
+    for option in args[1..].chunks(2) {
+        let [name, value] = option else { panic!("{} needs a value", option[0]) };
+        match name.as_str() {
+            "--seed" => knobs.seed = value.parse().unwrap(),
+            "--count" => knobs.count = value.parse().unwrap(),
+            "--width" => knobs.width = value.parse().unwrap(),
+            "--height" => knobs.height = value.parse().unwrap(),
+            "--density" => knobs.density = value.parse().unwrap(),
+            "--out" => out = Some(value.clone()),
+            _ => panic!("Unknown option {}", name),
+        }
+    }
 
-pub trait DaySolution<I, O> {
-    fn new() -> Self where Self: Sized;
-    fn day(&self) -> u16;
-    fn parse_input(&mut self, example: bool) -> I;
-    fn part1(&mut self, input: &I) -> O;
-    fn part2(&mut self, input: &I) -> O;
+    let input = generate(day, &knobs).unwrap_or_else(|| panic!("No generator for day {}", day));
+    match out.unwrap_or_else(|| format!("../data/day{:02}-gen-s{}.input", day, knobs.seed)).as_str() {
+        "-" => print!("{}", input),
+        path => {
+            fs::write(path, &input).unwrap();
+            println!("{} :: {} bytes, {:?}", path, input.len(), knobs);
+        }
+    }
 }
 
-const YEAR: &str = "2024";
-const LANG: &str = "[rust]";
-
-fn day_solution<I, O: Display>(mut solution: Box<dyn DaySolution<I, O>>) -> String {
-    let mut res = String::new();
-    res.push_str(&format!(
-        "----(AOC{} - Day {:02})-------------------{:->15}----\n",
-        YEAR, solution.day(), LANG
-    ));
-    let ex_data = solution.parse_input(true);
-    let p1_data = solution.parse_input(false);
-    res.push_str(&execute_solution(&ex_data, &mut solution, true, true));
-    res.push_str(&execute_solution(&ex_data, &mut solution, false, true));
-    res.push_str(&"------------------------------------------------------------\n");
-    res.push_str(&execute_solution(&p1_data, &mut solution, true, false));
-    res.push_str(&execute_solution(&p1_data, &mut solution, false, false));
-    res.push_str(&"------------------------------------------------------------\n");
+/// `scale DAY [--seed N] [--steps N] [--runs N]`
+///
+/// Times both parts on generated inputs from 1/16 of the official size, doubling `steps` times.
+fn scale_command(args: &[String]) {
+    let day = args[0].parse::<u16>().unwrap();
+    let (mut seed, mut steps, mut runs) = (0, 7, 3);
+    for option in args[1..].chunks(2) {
+        let [name, value] = option else { panic!("{} needs a value", option[0]) };
+        match name.as_str() {
+            "--seed" => seed = value.parse().unwrap(),
+            "--steps" => steps = value.parse().unwrap(),
+            "--runs" => runs = value.parse().unwrap(),
+            _ => panic!("Unknown option {}", name),
+        }
+    }
 
-    res
+    let factors = (0..steps).map(|step| 2_f64.powi(step - 4)).collect::<Vec<f64>>();
+    let scalings = scaling::measure(day, seed, &factors, runs).unwrap_or_else(|| panic!("No generator for day {}", day));
+    print!("{}", scaling::report(day, &scalings));
 }
 
-fn execute_solution<I, O: Display>(data: &I, solution: &mut Box<dyn DaySolution<I, O>>, part1: bool, example: bool) -> String {
-    let start = Instant::now();
-
-
-    let solution = if part1 {
-        solution.part1(&data)
-    } else {
-        solution.part2(&data)
+/// `minimize DAY FILE (--panics PART | --expect PART ANSWER | --reference) [--out FILE]`
+///
+/// Writes the smallest input found that still fails next to `FILE`, as `NAME.min.input`.
+fn minimize_command(args: &[String]) {
+    let day = args[0].parse::<u16>().unwrap();
+    let path = &args[1];
+    let part = |arg: &String| Part::try_from(arg.parse::<u8>().unwrap()).unwrap();
+    let (predicate, rest) = match &args[2..] {
+        [flag, p, rest @ ..] if flag == "--panics" => (Predicate::Panics(part(p)), rest),
+        [flag, p, answer, rest @ ..] if flag == "--expect" => (Predicate::Mismatch(part(p), answer.clone()), rest),
+        [flag, rest @ ..] if flag == "--reference" => (Predicate::Reference, rest),
+        _ => panic!("Expected --panics PART, --expect PART ANSWER or --reference"),
+    };
+    let out = match rest {
+        [flag, out] if flag == "--out" => out.clone(),
+        [] => format!("{}.min.input", path.trim_end_matches(".input")),
+        _ => panic!("Unknown options {:?}", rest),
     };
 
-    let time = (start.elapsed().as_micros() as f64) / 1_000_f64;
-    let part = if part1 { "Part 1" } else { "Part 2" };
-    let ex = if example { "Example" } else { "Input  " };
-
-    format!("{} :: {} ====> ({:10.3}ms) {:>20}\n", ex, part, time, solution)
+    let input = fs::read_to_string(path).unwrap();
+    match minimize(day, &input, &predicate) {
+        Ok(minimized) => {
+            fs::write(&out, &minimized).unwrap();
+            println!("{} :: {} bytes down to {}\n{}", out, input.len(), minimized.len(), minimized);
+        }
+        Err(error) => eprintln!("{} :: {}", path, error),
+    }
 }
 
-fn get_and_store_result<I, O: Display>(solution: Box<dyn DaySolution<I, O>>, day_range: Range<u16>) {
-    if !day_range.contains(&solution.day()) {
-        return;
+/// `[START_DAY [END_DAY]] [--strategy [PART=]NAME]... [--cross-check] [--bench RUNS]`
+/// `[--jobs N] [--timeout SECS] [--stats FILE]`
+///
+/// `--cross-check` runs every strategy of each part side by side instead of the report and
+/// exits with an error when they disagree, `--bench` does the same keeping the best of `RUNS`.
+/// `--jobs` runs days and the independent loops within them on `N` threads, which needs the
+/// `parallel` feature. `--timeout` gives up on a part, or on a strategy when cross-checking, after
+/// `SECS` seconds and moves on to the next day, leaving its result file as it was. `--stats`
+/// writes the time of parsing and of each part as tab-separated values, with their allocations
+/// when built with the `count-alloc` feature; a part that timed out gets a row noted `timeout`,
+/// and allocations that include those of other days running at the same time, with several jobs
+/// or after a timeout, are noted `unreliable`.
+fn run_command(args: &[String]) {
+    let mut days = vec![];
+    let mut options = Options { bench_runs: 1, ..Options::default() };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--strategy" => {
+                let value = args.next().expect("--strategy needs a name");
+                let strategy = match value.split_once('=') {
+                    Some((part, name)) => (Some(Part::try_from(part.parse::<u8>().unwrap()).unwrap()), name.to_string()),
+                    None => (None, value.clone()),
+                };
+                options.strategies.push(strategy);
+            }
+            "--cross-check" => options.cross_check = true,
+            "--bench" => {
+                options.cross_check = true;
+                options.bench_runs = args.next().expect("--bench needs a number of runs").parse().unwrap();
+            }
+            "--timeout" => {
+                let secs = args.next().expect("--timeout needs a number of seconds").parse().unwrap();
+                options.timeout = Some(Duration::from_secs_f64(secs));
+            }
+            "--stats" => options.stats = Some(args.next().expect("--stats needs a file").into()),
+            "--jobs" => {
+                let jobs = args.next().expect("--jobs needs a number of threads").parse().unwrap();
+                if !parallel::set_jobs(jobs) && jobs > 1 {
+                    eprintln!("Built without the parallel feature, running on one thread");
+                } else if memory::enabled() && jobs > 1 {
+                    eprintln!("Days running at the same time count each other's allocations, see the unreliable rows");
+                }
+            }
+            _ => days.push(arg.parse::<u16>().unwrap()),
+        }
     }
 
-    let day = solution.day();
-    let result = day_solution(solution);
-    println!("{}", &result);
-    tools::write_to_file(&result, day);
+    let day_range = match days[..] {
+        [] => 1..26,
+        [day] => day..day + 1,
+        [start_day, end_day, ..] => start_day..end_day + 1,
+    };
+    if let Err(disagreements) = runner::run(day_range, &options) {
+        disagreements.iter().for_each(|disagreement| eprintln!("{}", disagreement));
+        process::exit(1);
+    }
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let mut start_day = 1;
-    let mut end_day = 25;
-    if args.len() > 1 {
-        start_day = args[1].parse::<u16>().unwrap();
-        end_day = start_day + 1;
+    if args.len() > 2 && args[1] == "generate" {
+        generate_command(&args[2..]);
+        return;
     }
-    if args.len() > 2 {
-        end_day = args[2].parse::<u16>().unwrap() + 1;
+    if args.len() > 2 && args[1] == "minimize" {
+        minimize_command(&args[2..]);
+        return;
+    }
+    if args.len() > 2 && args[1] == "scale" {
+        scale_command(&args[2..]);
+        return;
     }
-    let day_range = start_day..end_day;
-    get_and_store_result(Box::new(day01::Day01::new()), day_range.clone());
-    get_and_store_result(Box::new(day02::Day02::new()), day_range.clone());
-    get_and_store_result(Box::new(day03::Day03::new()), day_range.clone());
-    get_and_store_result(Box::new(day04::Day04::new()), day_range.clone());
-    get_and_store_result(Box::new(day05::Day05::new()), day_range.clone());
-    get_and_store_result(Box::new(day06::Day06::new()), day_range.clone());
-    get_and_store_result(Box::new(day07::Day07::new()), day_range.clone());
-    get_and_store_result(Box::new(day08::Day08::new()), day_range.clone());
-    get_and_store_result(Box::new(day09::Day09::new()), day_range.clone());
-    get_and_store_result(Box::new(day10::Day10::new()), day_range.clone());
-    get_and_store_result(Box::new(day11::Day11::new()), day_range.clone());
+
+    run_command(&args[1..]);
 }
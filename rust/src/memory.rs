@@ -0,0 +1,124 @@
+//! Allocation counting behind the `count-alloc` feature: a global allocator on top of the system
+//! one that counts allocations, the bytes they ask for and the peak of live bytes. The counters
+//! are shared by all threads, so a measurement also counts what other measurements allocate at
+//! the same time: days running concurrently, or a part left behind by the timeout watchdog that
+//! is still running. Such figures are flagged as overlapped rather than split between them.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+/// Measurements running right now, and how many ever started.
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+static STARTED: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Counting;
+
+impl Counting {
+    fn grow(&self, size: usize) {
+        ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(live, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    /// Counts as a new allocation of `new_size` bytes, the old ones being freed.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+            self.grow(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+pub fn enabled() -> bool {
+    cfg!(feature = "count-alloc")
+}
+
+/// What some work allocated, all zero without the `count-alloc` feature.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub allocations: usize,
+    /// Bytes asked for, including those freed since.
+    pub bytes: usize,
+    /// The most bytes live at once, on top of those live when the work started.
+    pub peak: usize,
+    /// Whether another measurement ran at some point meanwhile, so that the figures include
+    /// some of its allocations and the peak may have been reset by it.
+    pub overlapped: bool,
+}
+
+/// Leaves the measurement even if the work panics, so that later ones are not flagged for it.
+struct Active;
+
+impl Drop for Active {
+    fn drop(&mut self) {
+        ACTIVE.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Runs `f` and counts what it allocates, threads it spreads work over included.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Usage) {
+    let started = STARTED.fetch_add(1, Ordering::SeqCst) + 1;
+    let others = ACTIVE.fetch_add(1, Ordering::SeqCst);
+    let active = Active;
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED.load(Ordering::Relaxed);
+    let live = LIVE.load(Ordering::Relaxed);
+    PEAK.store(live, Ordering::Relaxed);
+    let result = f();
+    let usage = Usage {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes: ALLOCATED.load(Ordering::Relaxed) - bytes,
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(live),
+        overlapped: enabled() && (others > 0 || STARTED.load(Ordering::SeqCst) != started),
+    };
+    drop(active);
+    (result, usage)
+}
+
+/// `bytes` in the largest binary unit that keeps it above 1, e.g. `1.5KiB`.
+pub fn human(bytes: usize) -> String {
+    let mut size = bytes as f64;
+    for unit in ["B", "KiB", "MiB"] {
+        if size < 1024.0 {
+            return if unit == "B" { format!("{}B", bytes) } else { format!("{:.1}{}", size, unit) };
+        }
+        size /= 1024.0;
+    }
+    format!("{:.1}GiB", size)
+}
@@ -0,0 +1,209 @@
+//! Delta debugging: shrinks a failing input while it keeps failing, first by whole lines, then by
+//! grid columns, then by single characters, until no removal keeps the failure.
+
+use std::cell::{Cell, RefCell};
+use std::fmt::{Display, Formatter};
+use std::panic;
+use std::sync::Once;
+use crate::day05::Day05;
+use crate::day09::{compact_by_brute_force, BlockWise, Disk, WholeFile};
+use crate::day11::{blink_by_brute_force, simulate};
+use crate::{puzzle, Part, DaySolution};
+
+/// What makes an input fail.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Predicate {
+    /// Solving the part panics at the same place as on the original input.
+    Panics(Part),
+    /// The part's answer is not the expected one.
+    Mismatch(Part, String),
+    /// The day's fast solver disagrees with its brute-force reference (Days 05, 09 and 11).
+    Reference,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MinimizeError {
+    UnknownDay(u16),
+    /// The predicate has nothing to compare for this day.
+    NoReference(u16),
+    /// The original input does not fail, so there is nothing to keep while shrinking.
+    NotFailing,
+}
+
+impl Display for MinimizeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MinimizeError::UnknownDay(day) => write!(f, "day {} is not solved", day),
+            MinimizeError::NoReference(day) => write!(f, "day {} has no brute-force reference", day),
+            MinimizeError::NotFailing => write!(f, "the input does not fail"),
+        }
+    }
+}
+
+impl std::error::Error for MinimizeError {}
+
+thread_local! {
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+    /// Set while [`minimize`] runs on this thread, whose panics are then recorded, not printed.
+    static MINIMIZING: Cell<bool> = const { Cell::new(false) };
+}
+
+static RECORDING_HOOK: Once = Once::new();
+
+/// Installs, once for the process, a hook that records where a minimizing thread panicked and
+/// defers to the previous hook everywhere else.
+fn install_recording_hook() {
+    RECORDING_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !MINIMIZING.with(Cell::get) {
+                return previous(info);
+            }
+            let location = info.location().map(|l| format!("{}:{}", l.file(), l.line())).unwrap_or_default();
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(location));
+        }));
+    });
+}
+
+/// Clears the minimizing flag of this thread when dropped, even if minimizing panics.
+struct Minimizing(bool);
+
+impl Drop for Minimizing {
+    fn drop(&mut self) {
+        MINIMIZING.with(|minimizing| minimizing.set(self.0));
+    }
+}
+
+/// Runs `f` with the panic message silenced, returning where it panicked if it did.
+fn panic_location<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    panic::catch_unwind(panic::AssertUnwindSafe(f)).map_err(|_| {
+        LAST_PANIC.with(|last| last.borrow_mut().take()).unwrap_or_default()
+    })
+}
+
+/// Solves a part on a fresh solution, `Err` with the panic location when it panics.
+fn answer(day: u16, input: &str, part: Part) -> Result<String, String> {
+    panic_location(|| {
+        let mut puzzle = puzzle(day).unwrap();
+        let data = puzzle.parse_input(input);
+        puzzle.solve(data.as_ref(), part).to_string()
+    })
+}
+
+pub fn has_reference(day: u16) -> bool {
+    matches!(day, 5 | 9 | 11)
+}
+
+/// Whether the day's fast path disagrees with its reference, `None` for a day without one.
+/// Day05 updates of more than 8 pages are left out: the reference tries every permutation.
+pub fn disagrees_with_reference(day: u16, input: &str) -> Option<bool> {
+    match day {
+        5 => {
+            let data = Day05::new().parse_input(input);
+            let updates = input.split("\n\n").nth(1).unwrap_or("").lines();
+            Some(updates
+                .map(|line| line.split(',').map(|page| page.trim().parse().unwrap()).collect::<Vec<i32>>())
+                .filter(|update| update.len() <= 8)
+                .any(|update| {
+                    data.is_sorted(&update) != data.is_sorted_by_brute_force(&update)
+                        || Some(data.reorder(&update)) != data.reorder_by_brute_force(&update)
+                }))
+        }
+        9 => {
+            let disk = Disk::from_map(input);
+            Some(disk.compact(&BlockWise).blocks() != compact_by_brute_force(&disk, false)
+                || disk.compact(&WholeFile).blocks() != compact_by_brute_force(&disk, true))
+        }
+        11 => {
+            let stones = input.split_whitespace().map(|stone| stone.parse().unwrap()).collect::<Vec<u64>>();
+            Some(simulate(&stones, 25).unwrap().total != blink_by_brute_force(&stones, 25).len() as u128)
+        }
+        _ => None,
+    }
+}
+
+/// A predicate turned into a check on candidate inputs. Candidates that are no longer valid
+/// inputs panic and count as passing, except for the very panic being chased.
+fn checker(day: u16, input: &str, predicate: &Predicate) -> Result<impl FnMut(&str) -> bool, MinimizeError> {
+    puzzle(day).ok_or(MinimizeError::UnknownDay(day))?;
+    if *predicate == Predicate::Reference && !has_reference(day) {
+        return Err(MinimizeError::NoReference(day));
+    }
+    let original_panic = match predicate {
+        Predicate::Panics(part) => answer(day, input, *part).err(),
+        _ => None,
+    };
+    let predicate = predicate.clone();
+    Ok(move |candidate: &str| match &predicate {
+        Predicate::Panics(part) => original_panic.is_some() && answer(day, candidate, *part).err() == original_panic,
+        Predicate::Mismatch(part, expected) => answer(day, candidate, *part).is_ok_and(|answer| answer != *expected),
+        Predicate::Reference => panic_location(|| disagrees_with_reference(day, candidate)) == Ok(Some(true)),
+    })
+}
+
+/// Removes ever smaller chunks of `units` as long as `fails` still holds on what is left.
+fn ddmin<T: Clone>(mut units: Vec<T>, join: impl Fn(&[T]) -> String, fails: &mut impl FnMut(&str) -> bool) -> Vec<T> {
+    let mut chunks = 2;
+    while !units.is_empty() {
+        let size = units.len().div_ceil(chunks);
+        let reduced = (0..units.len()).step_by(size).find_map(|start| {
+            let mut candidate = units[..start].to_vec();
+            candidate.extend_from_slice(&units[(start + size).min(units.len())..]);
+            fails(&join(&candidate)).then_some(candidate)
+        });
+        match reduced {
+            Some(candidate) => {
+                units = candidate;
+                chunks = (chunks - 1).max(2);
+            }
+            None if size == 1 => break,
+            None => chunks = (chunks * 2).min(units.len()),
+        }
+    }
+    units
+}
+
+/// Shrinks `input` while `fails` holds on it, which it must do at first.
+pub fn reduce(input: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+    let with_newline = |lines: &[&str]| lines.iter().map(|line| format!("{}\n", line)).collect::<String>();
+    let mut current = input.to_string();
+    loop {
+        let before = current.len();
+
+        let all_lines = current.lines().collect::<Vec<&str>>();
+        current = with_newline(&ddmin(all_lines, with_newline, &mut fails));
+
+        // Columns go together so that a grid stays a rectangle.
+        let rows = current.lines().map(|line| line.chars().collect()).collect::<Vec<Vec<char>>>();
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.len() > 1 && rows.iter().all(|row| row.len() == width) {
+            let keep = |columns: &[usize]| {
+                rows.iter()
+                    .map(|row| columns.iter().map(|c| row[*c]).collect::<String>() + "\n")
+                    .collect::<String>()
+            };
+            let columns = ddmin((0..width).collect(), keep, &mut fails);
+            current = keep(&columns);
+        }
+
+        let chars = current.chars().collect::<Vec<char>>();
+        current = ddmin(chars, |chars| chars.iter().collect(), &mut fails).into_iter().collect();
+
+        if current.len() == before {
+            return current;
+        }
+    }
+}
+
+/// The smallest input found that still fails as `input` does under `predicate`.
+pub fn minimize(day: u16, input: &str, predicate: &Predicate) -> Result<String, MinimizeError> {
+    install_recording_hook();
+    let _minimizing = Minimizing(MINIMIZING.with(|minimizing| minimizing.replace(true)));
+
+    checker(day, input, predicate).and_then(|mut fails| {
+        if !fails(input) {
+            return Err(MinimizeError::NotFailing);
+        }
+        Ok(reduce(input, fails))
+    })
+}
@@ -0,0 +1,201 @@
+//! Independent work spread over worker threads, behind the `parallel` feature. Results always
+//! come back in input order, so answers and reports do not depend on the number of jobs.
+//! Without the feature, or with one job, everything runs on the calling thread. Workers share the
+//! cancellation token of the thread that starts them.
+//!
+//! Workers live in a pool started on first use and kept for the whole process. A call hands its
+//! tasks to idle workers, starting new ones only when too few are idle, such as when some are
+//! still stuck in a solver the timeout watchdog gave up on.
+
+use std::any::Any;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, PoisonError};
+use std::thread;
+use crate::cancel;
+
+static JOBS: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+    /// Set on worker threads, so that work they spread runs on them instead of nesting threads.
+    static IN_WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Sets how many threads work may be spread over, `false` when built without `parallel`.
+pub fn set_jobs(jobs: usize) -> bool {
+    JOBS.store(jobs.max(1), Ordering::Relaxed);
+    cfg!(feature = "parallel")
+}
+
+/// How many threads the calling thread may spread work over right now.
+pub fn jobs() -> usize {
+    if !cfg!(feature = "parallel") || IN_WORKER.with(Cell::get) {
+        return 1;
+    }
+    JOBS.load(Ordering::Relaxed)
+}
+
+/// Starts a thread that may spread work over as many threads as the calling one.
+pub fn spawn(f: impl FnOnce() + Send + 'static) {
+    let in_worker = IN_WORKER.with(Cell::get);
+    thread::spawn(move || {
+        IN_WORKER.with(|flag| flag.set(in_worker));
+        f()
+    });
+}
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+struct Pool {
+    sender: Mutex<Sender<Job>>,
+    receiver: Arc<Mutex<Receiver<Job>>>,
+    /// Workers started so far, and those waiting for a task that no call has claimed.
+    workers: AtomicUsize,
+    idle: AtomicUsize,
+}
+
+static POOL: OnceLock<Pool> = OnceLock::new();
+
+fn pool() -> &'static Pool {
+    POOL.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        Pool {
+            sender: Mutex::new(sender),
+            receiver: Arc::new(Mutex::new(receiver)),
+            workers: AtomicUsize::new(0),
+            idle: AtomicUsize::new(0),
+        }
+    })
+}
+
+/// How many worker threads the pool has started so far.
+pub fn workers() -> usize {
+    POOL.get().map_or(0, |pool| pool.workers.load(Ordering::Relaxed))
+}
+
+impl Pool {
+    /// Claims `count` idle workers, starting the ones missing.
+    fn claim(&'static self, count: usize) {
+        let claimed = self.idle
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |idle| Some(idle - idle.min(count)))
+            .map_or(0, |idle| idle.min(count));
+        for _ in claimed..count {
+            self.workers.fetch_add(1, Ordering::Relaxed);
+            let receiver = Arc::clone(&self.receiver);
+            thread::spawn(move || {
+                IN_WORKER.with(|in_worker| in_worker.set(true));
+                loop {
+                    let job = receiver.lock().unwrap_or_else(PoisonError::into_inner).recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => return,
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Counts down the tasks of a call, which waits for all of them.
+struct Latch {
+    remaining: Mutex<usize>,
+    done: Condvar,
+}
+
+impl Latch {
+    fn count_down(&self) {
+        let mut remaining = self.remaining.lock().unwrap_or_else(PoisonError::into_inner);
+        *remaining -= 1;
+        if *remaining == 0 {
+            self.done.notify_all();
+        }
+    }
+
+    fn wait(&self) {
+        let mut remaining = self.remaining.lock().unwrap_or_else(PoisonError::into_inner);
+        while *remaining > 0 {
+            remaining = self.done.wait(remaining).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+type Outcome<R> = Mutex<Option<Result<R, Box<dyn Any + Send>>>>;
+
+/// Runs each task on a pool worker with the token of the calling thread, and returns their
+/// results in order once all are done. A panic in a task is raised again here.
+fn run_all<'a, R: Send + 'a>(tasks: Vec<Box<dyn FnOnce() -> R + Send + 'a>>) -> Vec<R> {
+    let pool = pool();
+    let latch = Latch { remaining: Mutex::new(tasks.len()), done: Condvar::new() };
+    let outcomes = tasks.iter().map(|_| Mutex::new(None)).collect::<Vec<Outcome<R>>>();
+    pool.claim(tasks.len());
+    let sender = pool.sender.lock().unwrap_or_else(PoisonError::into_inner).clone();
+    for (task, outcome) in tasks.into_iter().zip(&outcomes) {
+        let (token, latch) = (cancel::token(), &latch);
+        let job: Box<dyn FnOnce() + Send + '_> = Box::new(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| cancel::with_token(token, task)));
+            *outcome.lock().unwrap_or_else(PoisonError::into_inner) = Some(result);
+            pool.idle.fetch_add(1, Ordering::AcqRel);
+            latch.count_down();
+        });
+        // SAFETY: the job borrows `latch`, `outcomes` and what the task borrows, all of which
+        // outlive this call, which does not return before every job has counted the latch down.
+        // Nothing between sending and waiting can panic: the receiver lives as long as the pool.
+        let job = unsafe { std::mem::transmute::<Box<dyn FnOnce() + Send + '_>, Job>(job) };
+        sender.send(job).expect("the pool outlives its callers");
+    }
+    latch.wait();
+    outcomes.into_iter()
+        .map(|outcome| match outcome.into_inner().unwrap_or_else(PoisonError::into_inner) {
+            Some(Ok(result)) => result,
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => unreachable!("every task has run"),
+        })
+        .collect()
+}
+
+/// Calls `f` on consecutive chunks of `items`, one chunk per job, and concatenates the results.
+/// `f` gets whole chunks so that it can reuse buffers from one item to the next.
+pub fn map_chunks<T: Sync, R: Send>(items: &[T], f: impl Fn(&[T]) -> Vec<R> + Sync) -> Vec<R> {
+    let jobs = jobs().min(items.len());
+    if jobs <= 1 {
+        return f(items);
+    }
+    let size = items.len().div_ceil(jobs);
+    let f = &f;
+    let tasks = items.chunks(size)
+        .map(|chunk| Box::new(move || f(chunk)) as Box<dyn FnOnce() -> Vec<R> + Send>)
+        .collect();
+    run_all(tasks).into_iter().flatten().collect()
+}
+
+pub fn map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    map_chunks(items, |chunk| chunk.iter().map(&f).collect())
+}
+
+/// Like [`map`], but hands items out one at a time as workers become free, for items whose cost
+/// varies a lot, such as whole days.
+pub fn map_each<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let jobs = jobs().min(items.len());
+    if jobs <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let next = AtomicUsize::new(0);
+    let (f, next) = (&f, &next);
+    let tasks = (0..jobs)
+        .map(|_| {
+            Box::new(move || {
+                let mut done = vec![];
+                loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(idx) else { return done };
+                    done.push((idx, f(item)));
+                }
+            }) as Box<dyn FnOnce() -> Vec<(usize, R)> + Send>
+        })
+        .collect();
+    let mut results = run_all(tasks).into_iter().flatten().collect::<Vec<(usize, R)>>();
+    results.sort_by_key(|(idx, _)| *idx);
+    results.into_iter().map(|(_, result)| result).collect()
+}
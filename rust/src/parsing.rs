@@ -0,0 +1,192 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input does not follow the literal parts of the pattern.
+    Mismatch { pattern: String, input: String, at: usize },
+    /// The pattern has two `{}` placeholders with nothing in between.
+    AmbiguousPattern(String),
+    /// The number of captures does not match the requested tuple size.
+    Arity { expected: usize, found: usize },
+    /// A captured token could not be converted to the requested type.
+    Value { index: usize, token: String, message: String },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Mismatch { pattern, input, at } => {
+                write!(f, "input {:?} does not match pattern {:?} at byte {}", input, pattern, at)
+            }
+            ParseError::AmbiguousPattern(pattern) => {
+                write!(f, "pattern {:?} has adjacent placeholders", pattern)
+            }
+            ParseError::Arity { expected, found } => {
+                write!(f, "expected {} captures, found {}", expected, found)
+            }
+            ParseError::Value { index, token, message } => {
+                write!(f, "capture #{} ({:?}): {}", index, token, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn parse_token<T: FromStr>(index: usize, token: &str) -> Result<T, ParseError>
+where
+    T::Err: Display,
+{
+    token.parse::<T>().map_err(|e| ParseError::Value {
+        index,
+        token: token.to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// Extracts every integer of a line, a `-` directly before a digit is read as a sign.
+/// `"p=0,4 v=3,-3"` gives `[0, 4, 3, -3]`.
+pub fn ints<T: FromStr>(line: &str) -> Result<Vec<T>, ParseError>
+where
+    T::Err: Display,
+{
+    let bytes = line.as_bytes();
+    let mut res = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let signed = bytes[i] == b'-' && bytes.get(i + 1).is_some_and(|b| b.is_ascii_digit());
+        if !signed && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        res.push(parse_token(res.len(), &line[start..i])?);
+    }
+    Ok(res)
+}
+
+/// Splits the input into sections separated by blank lines, each section being its lines.
+/// Consecutive blank lines never produce empty sections.
+pub fn blocks(input: &str) -> Vec<Vec<&str>> {
+    let mut res = vec![];
+    let mut current = vec![];
+    for line in input.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                res.push(current);
+                current = vec![];
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        res.push(current);
+    }
+    res
+}
+
+pub fn char_grid(input: &str) -> Vec<Vec<char>> {
+    input.lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.chars().collect())
+        .collect()
+}
+
+pub fn digit_grid(input: &str) -> Result<Vec<Vec<u32>>, ParseError> {
+    input.lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.chars()
+                .enumerate()
+                .map(|(x, c)| {
+                    c.to_digit(10).ok_or_else(|| ParseError::Value {
+                        index: x,
+                        token: c.to_string(),
+                        message: "not a digit".to_string(),
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Matches `input` against a pattern where each `{}` captures text up to the next literal part
+/// (or the end of the input) and returns the raw captures, surrounding spaces trimmed.
+pub fn scan<'a>(pattern: &str, input: &'a str) -> Result<Vec<&'a str>, ParseError> {
+    let mismatch = |at: usize| ParseError::Mismatch {
+        pattern: pattern.to_string(),
+        input: input.to_string(),
+        at,
+    };
+
+    let literals = pattern.split("{}").collect::<Vec<&str>>();
+    if literals.len() > 2 && literals[1..literals.len() - 1].iter().any(|l| l.is_empty()) {
+        return Err(ParseError::AmbiguousPattern(pattern.to_string()));
+    }
+
+    let mut captures = vec![];
+    let mut rest = input.strip_prefix(literals[0]).ok_or_else(|| mismatch(0))?;
+    for (idx, literal) in literals.iter().enumerate().skip(1) {
+        let at = input.len() - rest.len();
+        let end = if idx == literals.len() - 1 {
+            rest.strip_suffix(literal).map(|r| r.len()).ok_or_else(|| mismatch(at))?
+        } else {
+            rest.find(literal).ok_or_else(|| mismatch(at))?
+        };
+        captures.push(rest[..end].trim());
+        rest = &rest[end + literal.len()..];
+    }
+    if !rest.is_empty() {
+        return Err(mismatch(input.len() - rest.len()));
+    }
+    Ok(captures)
+}
+
+/// Tuples that can be built from the captures of [`scan`], used by [`parse!`](crate::parse).
+pub trait FromCaptures: Sized {
+    fn from_captures(captures: &[&str]) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_from_captures {
+    ($len:expr; $($t:ident $idx:tt),+) => {
+        impl<$($t: FromStr),+> FromCaptures for ($($t,)+)
+        where
+            $($t::Err: Display),+
+        {
+            fn from_captures(captures: &[&str]) -> Result<Self, ParseError> {
+                if captures.len() != $len {
+                    return Err(ParseError::Arity { expected: $len, found: captures.len() });
+                }
+                Ok(($(parse_token::<$t>($idx, captures[$idx])?,)+))
+            }
+        }
+    };
+}
+
+impl_from_captures!(1; A 0);
+impl_from_captures!(2; A 0, B 1);
+impl_from_captures!(3; A 0, B 1, C 2);
+impl_from_captures!(4; A 0, B 1, C 2, D 3);
+impl_from_captures!(5; A 0, B 1, C 2, D 3, E 4);
+impl_from_captures!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+impl_from_captures!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_from_captures!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+pub fn parse_captures<T: FromCaptures>(pattern: &str, input: &str) -> Result<T, ParseError> {
+    T::from_captures(&scan(pattern, input)?)
+}
+
+/// scanf-like parsing into a typed tuple:
+/// `let (px, py, vx, vy): (i32, i32, i32, i32) = parse!("p={},{} v={},{}", line)?;`
+#[macro_export]
+macro_rules! parse {
+    ($pattern:expr, $input:expr) => {
+        $crate::parsing::parse_captures($pattern, $input)
+    };
+}
@@ -0,0 +1,313 @@
+use std::any::Any;
+use std::fs;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use crate::memory::{self, Usage};
+use crate::{cancel, parallel, puzzle, puzzles, scaling, tools, Part, Puzzle, DEFAULT_STRATEGY, LANG, YEAR};
+
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// Strategies by name, for one part or for both with `None`. Days without a strategy of
+    /// that name keep their default.
+    pub strategies: Vec<(Option<Part>, String)>,
+    /// Runs every strategy of each part instead, side by side, and fails if their answers differ.
+    pub cross_check: bool,
+    /// How many times each strategy runs when cross-checking, keeping the best time.
+    pub bench_runs: usize,
+    /// How long a part may run before it is reported as a timeout and the day is left behind.
+    pub timeout: Option<Duration>,
+    /// Where to write the time and allocations of every stage as tab-separated values.
+    pub stats: Option<PathBuf>,
+}
+
+impl Options {
+    /// Picks the strategies the day knows, returns the name used per part when not the default.
+    fn apply_strategies(&self, puzzle: &mut dyn Puzzle) -> [Option<String>; 2] {
+        let mut chosen = [None, None];
+        for (part, name) in &self.strategies {
+            for part in part.map_or(vec![Part::One, Part::Two], |part| vec![part]) {
+                let strategies = puzzle.strategies(part);
+                if puzzle.use_strategy(part, name) {
+                    chosen[part.index()] = (strategies[0] != name).then(|| name.clone());
+                } else if strategies.len() > 1 {
+                    eprintln!("Day {:02} {} has no strategy {}, only {}", puzzle.day(), part, name, strategies.join(", "));
+                }
+            }
+        }
+        chosen
+    }
+}
+
+/// What a day sends back while it runs, so that a watchdog can give up on one of its parts.
+enum Event {
+    /// A part starts, with the start of its line, the token that cancels it and the row of
+    /// statistics to write if it times out.
+    Part(String, cancel::Token, String),
+    Line(String),
+    /// A row of the machine-readable statistics, sent after the line of its stage.
+    Row(String),
+    /// Two strategies of a part that give different answers, when cross-checking.
+    Disagreement(String),
+}
+
+/// Emits the report line by line, and stops after a part that was cancelled.
+fn day_solution(puzzle: &mut dyn Puzzle, options: &Options, emit: &mut dyn FnMut(Event)) {
+    let day = puzzle.day();
+    emit(Event::Line(format!(
+        "----(AOC{} - Day {:02})-------------------{:->15}----\n",
+        YEAR, day, LANG
+    )));
+    let chosen = options.apply_strategies(puzzle);
+    let mut ex_data = None;
+    for example in [true, false] {
+        let raw_data = tools::read_raw_data(day, example);
+        let (data, time, usage) = measured(|| puzzle.parse_input(&raw_data));
+        if memory::enabled() {
+            let label = format!("{} :: Parse ", input_name(example));
+            emit(Event::Line(format!("{} ====> ({:10.3}ms) {:>20}{}\n", label, time, "", usage_columns(usage))));
+        }
+        emit(Event::Row(stats_row(day, example, "parse", DEFAULT_STRATEGY, time, usage)));
+        for part in [Part::One, Part::Two] {
+            if !execute_solution(data.as_ref(), puzzle, part, &chosen, example, emit) {
+                return;
+            }
+        }
+        emit(Event::Line(SEPARATOR.to_string()));
+        if example {
+            ex_data = Some(data);
+        }
+    }
+    if let Some(render) = puzzle.render(ex_data.unwrap().as_ref()) {
+        emit(Event::Line(render));
+        emit(Event::Line(SEPARATOR.to_string()));
+    }
+}
+
+const SEPARATOR: &str = "------------------------------------------------------------\n";
+
+fn input_name(example: bool) -> &'static str {
+    if example { "Example" } else { "Input  " }
+}
+
+/// Runs `f`, with its time in milliseconds and what it allocated.
+fn measured<R>(f: impl FnOnce() -> R) -> (R, f64, Usage) {
+    let start = Instant::now();
+    let (result, usage) = memory::measure(f);
+    let time = (start.elapsed().as_micros() as f64) / 1_000_f64;
+    (result, time, usage)
+}
+
+/// The extra report columns with the allocations of a stage, empty when they are not counted.
+fn usage_columns(usage: Usage) -> String {
+    if !memory::enabled() {
+        return String::new();
+    }
+    format!(
+        " {:>9} allocs {:>9} total {:>9} peak{}",
+        usage.allocations, memory::human(usage.bytes), memory::human(usage.peak),
+        if usage.overlapped { " (unreliable)" } else { "" }
+    )
+}
+
+/// The tab-separated header of the statistics written with `--stats`.
+pub const STATS_HEADER: &str = "day\tinput\tstage\tstrategy\tmillis\tallocations\tbytes\tpeak_bytes\tnote";
+
+/// A row of statistics, with `-` for allocations when they are not counted and noted
+/// `unreliable` when they include those of other stages running at the same time.
+fn stats_row(day: u16, example: bool, stage: &str, strategy: &str, time: f64, usage: Usage) -> String {
+    let columns = if memory::enabled() {
+        format!("{}\t{}\t{}", usage.allocations, usage.bytes, usage.peak)
+    } else {
+        "-\t-\t-".to_string()
+    };
+    let note = if usage.overlapped { "unreliable" } else { "" };
+    format!("{}\t{:.3}\t{}\t{}", stage_columns(day, example, stage, strategy), time, columns, note)
+}
+
+/// The row of a stage that ran out of time, with nothing measured.
+fn timeout_row(day: u16, example: bool, stage: &str, strategy: &str) -> String {
+    format!("{}\t-\t-\t-\t-\ttimeout", stage_columns(day, example, stage, strategy))
+}
+
+fn stage_columns(day: u16, example: bool, stage: &str, strategy: &str) -> String {
+    let input = if example { "example" } else { "input" };
+    format!("{}\t{}\t{}\t{}", day, input, stage, strategy)
+}
+
+/// Solves a part and emits its line, `false` if it was cancelled meanwhile.
+fn execute_solution(data: &dyn Any, puzzle: &mut dyn Puzzle, part: Part, chosen: &[Option<String>; 2], example: bool, emit: &mut dyn FnMut(Event)) -> bool {
+    let ex = input_name(example);
+    let label = match &chosen[part.index()] {
+        Some(strategy) => format!("{} :: {} [{}]", ex, part, strategy),
+        None => format!("{} :: {}", ex, part),
+    };
+    let stage = format!("part{}", part.index() + 1);
+    let strategy = chosen[part.index()].as_deref().unwrap_or(DEFAULT_STRATEGY);
+    let token = cancel::Token::default();
+    emit(Event::Part(label.clone(), token.clone(), timeout_row(puzzle.day(), example, &stage, strategy)));
+
+    let (solution, time, usage) = measured(|| cancel::with_token(Some(token.clone()), || puzzle.solve(data, part)));
+
+    if cancel::is_cancelled(&token) {
+        return false;
+    }
+
+    emit(Event::Line(format!("{} ====> ({:10.3}ms) {:>20}{}\n", label, time, solution, usage_columns(usage))));
+    emit(Event::Row(stats_row(puzzle.day(), example, &stage, strategy, time, usage)));
+    true
+}
+
+/// Runs the day `make` builds on a thread of its own and gives up on it when a part takes longer
+/// than `limit`. The part is cancelled and reported as a timeout, and its thread is left behind if
+/// the solver never checks for cancellation.
+pub fn watch(make: impl FnOnce() -> Box<dyn Puzzle> + Send + 'static, options: &Options, limit: Duration) -> Report {
+    let options = options.clone();
+    watch_events(move |emit| day_solution(make().as_mut(), &options, emit), limit)
+}
+
+/// Runs `work` on a thread of its own and collects what it emits, giving up when a part takes
+/// longer than `limit`.
+fn watch_events(work: impl FnOnce(&mut dyn FnMut(Event)) + Send + 'static, limit: Duration) -> Report {
+    let (sender, receiver) = mpsc::channel();
+    parallel::spawn(move || {
+        work(&mut |event| {
+            let _ = sender.send(event);
+        })
+    });
+
+    let mut report = Report::default();
+    while let Ok(event) = receiver.recv() {
+        match event {
+            Event::Part(label, token, timeout_row) => match receiver.recv_timeout(limit) {
+                Ok(event) => report.add(event),
+                Err(RecvTimeoutError::Timeout) => {
+                    cancel::cancel(&token);
+                    report.text.push_str(&format!("{} ====> TIMEOUT after {}s\n", label, limit.as_secs_f64()));
+                    report.text.push_str(SEPARATOR);
+                    report.rows.push(timeout_row);
+                    report.timed_out = true;
+                    break;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            },
+            event => report.add(event),
+        }
+    }
+    report
+}
+
+/// Emits every strategy of each part, side by side, and the disagreements found, stopping after
+/// a strategy that was cancelled.
+fn cross_check(day: u16, options: &Options, emit: &mut dyn FnMut(Event)) {
+    emit(Event::Line(format!("----(AOC{} - Day {:02} - strategies)-------------------------\n", YEAR, day)));
+    let width = [Part::One, Part::Two].iter()
+        .flat_map(|part| puzzle(day).unwrap().strategies(*part))
+        .map(|strategy| strategy.len())
+        .max()
+        .unwrap_or(0);
+    for example in [true, false] {
+        let raw_data = tools::read_raw_data(day, example);
+        let ex = input_name(example);
+        for part in [Part::One, Part::Two] {
+            let strategies = puzzle(day).unwrap().strategies(part);
+            let mut expected = None;
+            for strategy in strategies {
+                let label = format!("{} :: {} :: {:<w$}", ex, part, strategy, w = width);
+                let stage = format!("part{}", part.index() + 1);
+                let token = cancel::Token::default();
+                emit(Event::Part(label.clone(), token.clone(), timeout_row(day, example, &stage, strategy)));
+                let (time, answer) = cancel::with_token(Some(token.clone()), || {
+                    scaling::time(day, &raw_data, part, Some(strategy), options.bench_runs)
+                });
+                if cancel::is_cancelled(&token) {
+                    return;
+                }
+                let (first, expected) = expected.get_or_insert_with(|| (*strategy, answer.clone()));
+                let verdict = if answer == *expected { "" } else { "  <== DISAGREES" };
+                emit(Event::Line(format!("{} ====> ({:10.3}ms) {:>20}{}\n", label, time, answer, verdict)));
+                if answer != *expected {
+                    emit(Event::Disagreement(format!("Day {:02} {} {} :: {} gives {}, {} gives {}", day, ex.trim(), part, first, expected, strategy, answer)));
+                }
+            }
+        }
+        emit(Event::Line(SEPARATOR.to_string()));
+    }
+}
+
+/// The report of one day, its statistics and the disagreements found, if cross-checking.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub text: String,
+    /// Rows of statistics, laid out as [`STATS_HEADER`] says.
+    pub rows: Vec<String>,
+    pub disagreements: Vec<String>,
+    /// Whether the watchdog gave up on a part, leaving the report unfinished.
+    pub timed_out: bool,
+}
+
+impl Report {
+    fn add(&mut self, event: Event) {
+        match event {
+            Event::Line(line) => self.text.push_str(&line),
+            Event::Row(row) => self.rows.push(row),
+            Event::Disagreement(disagreement) => self.disagreements.push(disagreement),
+            Event::Part(..) => {}
+        }
+    }
+}
+
+fn day_report(day: u16, options: &Options) -> Report {
+    match (options.cross_check, options.timeout) {
+        (true, Some(limit)) => {
+            let options = options.clone();
+            watch_events(move |emit| cross_check(day, &options, emit), limit)
+        }
+        (false, Some(limit)) => watch(move || puzzle(day).unwrap(), options, limit),
+        (cross_checking, None) => {
+            let mut report = Report::default();
+            if cross_checking {
+                cross_check(day, options, &mut |event| report.add(event));
+            } else {
+                day_solution(puzzle(day).unwrap().as_mut(), options, &mut |event| report.add(event));
+            }
+            report
+        }
+    }
+}
+
+/// Runs every day in `day_range` on its data files, prints the report and stores it, or only
+/// prints the strategies side by side when cross-checking. A report cut short by the watchdog
+/// is not stored, so that the result file keeps the last complete one. The disagreements found, if any,
+/// make the error. With several jobs, days run concurrently and are printed in order once all
+/// are done. The time and allocations of every stage go to `options.stats` if given.
+pub fn run(day_range: Range<u16>, options: &Options) -> Result<(), Vec<String>> {
+    let days = puzzles().iter()
+        .map(|puzzle| puzzle.day())
+        .filter(|day| day_range.contains(day))
+        .collect::<Vec<u16>>();
+    let mut disagreements = vec![];
+    let mut rows = vec![STATS_HEADER.to_string()];
+    let mut publish = |day: u16, mut report: Report| {
+        println!("{}", &report.text);
+        if !options.cross_check && !report.timed_out {
+            tools::write_to_file(&report.text, day);
+        }
+        disagreements.append(&mut report.disagreements);
+        rows.append(&mut report.rows);
+    };
+    if parallel::jobs() > 1 {
+        let reports = parallel::map_each(&days, |day| day_report(*day, options));
+        days.iter().zip(reports).for_each(|(day, report)| publish(*day, report));
+    } else {
+        days.iter().for_each(|day| publish(*day, day_report(*day, options)));
+    }
+    if let Some(path) = &options.stats {
+        let stats = rows.iter().map(|row| format!("{}\n", row)).collect::<String>();
+        if let Err(error) = fs::write(path, stats) {
+            eprintln!("{} :: {}", path.display(), error);
+        }
+    }
+    if disagreements.is_empty() { Ok(()) } else { Err(disagreements) }
+}
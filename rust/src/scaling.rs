@@ -0,0 +1,194 @@
+//! How a day's running time grows with the size of its input. Inputs come from the generators,
+//! scaled from the official knobs, and time is fitted as `n ^ exponent` on a log-log scale, `n`
+//! being the knob that grows: cells on a grid, the count otherwise. Bytes would hide a fixed part,
+//! like the rules of Day05.
+
+use std::time::Instant;
+use crate::generate::{generate, Knobs};
+use crate::{puzzle, Answer, Part};
+
+/// Below this a time is mostly noise, so it is left out of the fit.
+const MIN_FIT_MILLIS: f64 = 0.05;
+/// How far above the expected exponent the fitted one may go before it is flagged.
+const TOLERANCE: f64 = 0.25;
+
+/// The exponent each part should show in the input size, from the complexity its algorithm
+/// should have rather than from what was measured, so that a regression shows.
+pub fn expected_exponent(day: u16, part: Part) -> f64 {
+    match (day, part) {
+        // Part 2 replays the walk for each cell of the path, which grows with the map's side.
+        (6, Part::Two) => 1.5,
+        // Every pair of antennas of a frequency, and resonances walk whole lines.
+        (8, Part::One) => 2.0,
+        (8, Part::Two) => 2.5,
+        // Trails are 10 cells long whatever the map, so Day10 is linear in its cells.
+        _ => 1.0,
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Sample {
+    pub n: usize,
+    /// Input length in bytes.
+    pub bytes: usize,
+    pub millis: f64,
+}
+
+pub struct Scaling {
+    pub part: Part,
+    pub samples: Vec<Sample>,
+    /// `None` when fewer than two samples were slow enough to fit.
+    pub exponent: Option<f64>,
+    pub expected: f64,
+}
+
+impl Scaling {
+    pub fn is_worse_than_expected(&self) -> bool {
+        self.exponent.is_some_and(|exponent| exponent > self.expected + TOLERANCE)
+    }
+}
+
+/// The knobs with the input size multiplied by `factor`: counts directly, grid sides by its square
+/// root so that the number of cells follows. A `width` without a `height` is not a grid (pages,
+/// numbers per equation) and keeps its value, as does the shape of each line.
+pub fn scale(knobs: &Knobs, factor: f64) -> Knobs {
+    let by = |n: usize, f: f64| if n == 0 { 0 } else { ((n as f64 * f).round() as usize).max(1) };
+    let side = if knobs.height > 0 { factor.sqrt() } else { 1.0 };
+    Knobs {
+        count: by(knobs.count, factor),
+        width: by(knobs.width, side),
+        height: by(knobs.height, side),
+        ..knobs.clone()
+    }
+}
+
+fn size(knobs: &Knobs) -> usize {
+    if knobs.height > 0 { knobs.width * knobs.height } else { knobs.count }
+}
+
+/// Least-squares slope of `ln(millis)` against `ln(n)`.
+pub fn fit(samples: &[Sample]) -> Option<f64> {
+    let points = samples.iter()
+        .filter(|sample| sample.millis >= MIN_FIT_MILLIS)
+        .map(|sample| ((sample.n as f64).ln(), sample.millis.ln()))
+        .collect::<Vec<(f64, f64)>>();
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let covariance = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum::<f64>();
+    let variance = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum::<f64>();
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+/// The best time in milliseconds of `runs` runs, each on a fresh solution so that no cache
+/// carries over, and the answer. `strategy` picks how the part is solved, the default with `None`.
+pub fn time(day: u16, input: &str, part: Part, strategy: Option<&str>, runs: usize) -> (f64, Answer) {
+    let mut best = (f64::INFINITY, None);
+    for _ in 0..runs.max(1) {
+        let mut puzzle = puzzle(day).unwrap();
+        if let Some(strategy) = strategy {
+            puzzle.use_strategy(part, strategy);
+        }
+        let data = puzzle.parse_input(input);
+        let start = Instant::now();
+        let answer = puzzle.solve(data.as_ref(), part);
+        best = (best.0.min(start.elapsed().as_secs_f64() * 1000.0), Some(answer));
+    }
+    (best.0, best.1.unwrap())
+}
+
+/// Times both parts on inputs scaled by each of `factors`, `None` for a day without a generator
+/// or without a solution.
+pub fn measure(day: u16, seed: u64, factors: &[f64], runs: usize) -> Option<Vec<Scaling>> {
+    puzzle(day)?;
+    let knobs = Knobs { seed, ..Knobs::official(day) };
+    let inputs = factors.iter()
+        .map(|factor| {
+            let knobs = scale(&knobs, *factor);
+            generate(day, &knobs).map(|input| (size(&knobs), input))
+        })
+        .collect::<Option<Vec<(usize, String)>>>()?;
+
+    let scalings = [Part::One, Part::Two].into_iter()
+        .map(|part| {
+            let samples = inputs.iter()
+                .map(|(n, input)| Sample { n: *n, bytes: input.len(), millis: time(day, input, part, None, runs).0 })
+                .collect::<Vec<Sample>>();
+            Scaling { part, exponent: fit(&samples), samples, expected: expected_exponent(day, part) }
+        })
+        .collect();
+    Some(scalings)
+}
+
+const CHART_WIDTH: usize = 50;
+const CHART_HEIGHT: usize = 12;
+
+/// Samples as `*` on a log-log grid, the fitted line as `.` behind them.
+fn chart(scaling: &Scaling) -> String {
+    let points = scaling.samples.iter()
+        .map(|sample| ((sample.n as f64).log10(), sample.millis.max(1e-4).log10()))
+        .collect::<Vec<(f64, f64)>>();
+    let (min_x, max_x) = points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (x, _)| (lo.min(*x), hi.max(*x)));
+    let (min_y, max_y) = points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (_, y)| (lo.min(*y), hi.max(*y)));
+    let column = |x: f64| (((x - min_x) / (max_x - min_x).max(1e-9)) * (CHART_WIDTH - 1) as f64).round() as usize;
+    let row = |y: f64| (((max_y - y) / (max_y - min_y).max(1e-9)) * (CHART_HEIGHT - 1) as f64).round();
+
+    let mut grid = vec![vec![' '; CHART_WIDTH]; CHART_HEIGHT];
+    if let Some(exponent) = scaling.exponent {
+        // The fitted line goes through the mean of the points.
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / points.len() as f64;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / points.len() as f64;
+        (0..CHART_WIDTH).for_each(|col| {
+            let x = min_x + (max_x - min_x) * col as f64 / (CHART_WIDTH - 1) as f64;
+            let r = row(mean_y + exponent * (x - mean_x));
+            if (0.0..CHART_HEIGHT as f64).contains(&r) {
+                grid[r as usize][col] = '.';
+            }
+        });
+    }
+    points.iter().for_each(|(x, y)| grid[row(*y) as usize][column(*x)] = '*');
+
+    let mut res = String::new();
+    for (r, line) in grid.iter().enumerate() {
+        let label = match r {
+            0 => format!("{:>9.3}ms", 10_f64.powf(max_y)),
+            _ if r == CHART_HEIGHT - 1 => format!("{:>9.3}ms", 10_f64.powf(min_y)),
+            _ => String::new(),
+        };
+        res.push_str(&format!("{:>11} |{}\n", label, line.iter().collect::<String>()));
+    }
+    res.push_str(&format!("{:>11} +{}\n", "", "-".repeat(CHART_WIDTH)));
+    res.push_str(&format!(
+        "{:>11}  {:<w$}{:>10}\n",
+        "",
+        format!("n={}", 10_f64.powf(min_x).round()),
+        format!("n={}", 10_f64.powf(max_x).round()),
+        w = CHART_WIDTH - 10
+    ));
+    res
+}
+
+pub fn report(day: u16, scalings: &[Scaling]) -> String {
+    let mut res = String::new();
+    res.push_str(&format!("----(Day {:02} - scaling)--------------------------------------\n", day));
+    for scaling in scalings {
+        res.push_str(&format!("{} ::\n", scaling.part));
+        for sample in &scaling.samples {
+            res.push_str(&format!("    n = {:>8} ({:>9} bytes) ====> ({:10.3}ms)\n", sample.n, sample.bytes, sample.millis));
+        }
+        let verdict = match scaling.exponent {
+            None => "too fast to fit".to_string(),
+            Some(exponent) if scaling.is_worse_than_expected() => {
+                format!("n^{:.2}, WORSE than the expected n^{:.1}", exponent, scaling.expected)
+            }
+            Some(exponent) => format!("n^{:.2}, expected n^{:.1}", exponent, scaling.expected),
+        };
+        res.push_str(&format!("    time ~ {}\n", verdict));
+        res.push_str(&chart(scaling));
+    }
+    res.push_str("------------------------------------------------------------\n");
+    res
+}
@@ -0,0 +1,76 @@
+use rust::{puzzle, puzzles, solve, Error, Part};
+
+#[test]
+fn solves_an_example_from_its_text() {
+    let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+    assert_eq!(solve(2024, 1, Part::One, input).unwrap().to_string(), "11");
+    assert_eq!(solve(2024, 1, Part::Two, input).unwrap().to_string(), "31");
+}
+
+#[test]
+fn answers_keep_wide_integers() {
+    assert_eq!(solve(2024, 11, Part::Two, "125 17\n").unwrap().as_str(), "65601038650482");
+}
+
+#[test]
+fn rejects_other_years() {
+    assert_eq!(solve(2023, 1, Part::One, ""), Err(Error::UnsupportedYear(2023)));
+}
+
+#[test]
+fn rejects_unsolved_days() {
+    assert_eq!(solve(2024, 26, Part::One, ""), Err(Error::UnknownDay(26)));
+}
+
+#[test]
+fn parts_come_from_numbers() {
+    assert_eq!(Part::try_from(2), Ok(Part::Two));
+    assert_eq!(Part::try_from(3), Err(Error::UnknownPart(3)));
+}
+
+#[test]
+fn registry_lists_days_in_order() {
+    let days = puzzles().iter().map(|puzzle| puzzle.day()).collect::<Vec<u16>>();
+    assert_eq!(days, (1..=11).collect::<Vec<u16>>());
+    assert!(puzzle(12).is_none());
+}
+
+#[test]
+fn puzzles_reuse_a_parsed_input() {
+    let mut day = puzzle(3).unwrap();
+    let input = day.parse_input("xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))");
+    assert_eq!(day.solve(input.as_ref(), Part::One).to_string(), "161");
+    assert_eq!(day.solve(input.as_ref(), Part::Two).to_string(), "161");
+}
+
+#[test]
+fn strategies_are_picked_by_name() {
+    let mut day = puzzle(11).unwrap();
+    assert_eq!(day.strategies(Part::Two), ["count-map", "memo"]);
+    assert!(day.use_strategy(Part::One, "literal"));
+    assert!(!day.use_strategy(Part::Two, "literal"));
+    let mut day = puzzle(1).unwrap();
+    assert_eq!(day.strategies(Part::One), [rust::DEFAULT_STRATEGY]);
+    assert!(!day.use_strategy(Part::One, "fast"));
+}
+
+#[test]
+fn malformed_inputs_are_parse_errors() {
+    let error = solve(2024, 1, Part::One, "3   4\n4   99999999999\n").unwrap_err();
+    assert!(matches!(error, Error::Parse { day: 1, .. }), "{:?}", error);
+    assert!(error.to_string().starts_with("day 1 cannot solve this input: "), "{}", error);
+    assert!(matches!(solve(2024, 11, Part::One, "125 x17\n"), Err(Error::Parse { day: 11, .. })));
+    assert_eq!(solve(2024, 1, Part::One, "3   4\n").unwrap().as_str(), "1");
+}
+
+#[test]
+fn every_listed_strategy_can_be_picked() {
+    for mut day in puzzles() {
+        for part in [Part::One, Part::Two] {
+            for strategy in day.strategies(part) {
+                assert!(day.use_strategy(part, strategy), "day {} {} {}", day.day(), part, strategy);
+            }
+            assert!(!day.use_strategy(part, "no-such-strategy"), "day {} {}", day.day(), part);
+        }
+    }
+}
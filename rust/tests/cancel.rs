@@ -0,0 +1,75 @@
+use std::collections::HashSet;
+use std::thread;
+use std::time::Duration;
+use rust::{cancel, parallel, puzzle, Part};
+
+#[test]
+fn tokens_are_scoped_to_the_work() {
+    let token = cancel::Token::default();
+    assert!(!cancel::cancelled());
+    cancel::with_token(Some(token.clone()), || {
+        assert!(!cancel::cancelled());
+        cancel::cancel(&token);
+        assert!(cancel::cancelled());
+        assert!(parallel::map(&[1, 2, 3], |_| cancel::cancelled()).into_iter().all(|cancelled| cancelled));
+    });
+    assert!(!cancel::cancelled());
+    assert!(cancel::is_cancelled(&token));
+}
+
+/// A cancelled solver gives up right away instead of finishing, whatever it answers.
+#[test]
+fn solvers_check_for_cancellation() {
+    let mut day = puzzle(7).unwrap();
+    let data = day.parse_input("190: 10 19\n3267: 81 40 27\n292: 11 6 16 20\n");
+    let token = cancel::Token::default();
+    cancel::cancel(&token);
+    let answer = cancel::with_token(Some(token), || day.solve(data.as_ref(), Part::Two));
+    assert_eq!(answer.as_str(), "0");
+    assert_eq!(day.solve(data.as_ref(), Part::Two).as_str(), "3749");
+}
+
+/// Workers spin until the token is raised from another thread, so the test only ends if the
+/// token reaches every one of them. This is the only test here that changes the number of jobs.
+#[test]
+fn cancelling_stops_every_worker() {
+    let threaded = parallel::set_jobs(4);
+    let token = cancel::Token::default();
+    let canceller = {
+        let token = token.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            cancel::cancel(&token);
+        })
+    };
+    let items = (0..8).collect::<Vec<u32>>();
+    let (threads, each) = cancel::with_token(Some(token.clone()), || {
+        let threads = parallel::map(&items, |_| {
+            while !cancel::cancelled() {
+                thread::yield_now();
+            }
+            thread::current().id()
+        });
+        (threads, parallel::map_each(&items, |_| cancel::cancelled()))
+    });
+    canceller.join().unwrap();
+    assert_eq!(threads.iter().collect::<HashSet<_>>().len(), if threaded { 4 } else { 1 });
+    assert!(each.into_iter().all(|cancelled| cancelled));
+
+    let mut day = puzzle(7).unwrap();
+    let data = day.parse_input("190: 10 19\n3267: 81 40 27\n292: 11 6 16 20\n");
+    let answer = cancel::with_token(Some(token), || day.solve(data.as_ref(), Part::Two));
+    assert_eq!(answer.as_str(), "0");
+}
+
+#[test]
+fn tokens_are_restored_after_a_panic() {
+    let outer = cancel::Token::default();
+    cancel::cancel(&outer);
+    cancel::with_token(Some(outer), || {
+        let inner = std::panic::catch_unwind(|| cancel::with_token(None, || panic!("solver gave up")));
+        assert!(inner.is_err());
+        assert!(cancel::cancelled());
+    });
+    assert!(!cancel::cancelled());
+}
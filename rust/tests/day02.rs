@@ -0,0 +1,59 @@
+use rust::day02::SafetyRules;
+use rust::generate::Rng;
+
+/// A random walk, so that a fair share of the reports are safe or nearly so.
+fn report(rng: &mut Rng) -> Vec<i32> {
+    let len = rng.between(0, 8);
+    let mut level = rng.between(0, 20) as i32;
+    (0..len)
+        .map(|_| {
+            level += rng.between(0, 8) as i32 - 4;
+            level
+        })
+        .collect()
+}
+
+/// The fewest levels to drop for the report to be safe, trying every way up to `max_removals`.
+fn fewest_removals(report: &[i32], rules: &SafetyRules) -> Option<usize> {
+    (0..=rules.max_removals).find(|k| SafetyRules { steps: rules.steps.clone(), max_removals: *k }.is_safe_by_brute_force(report))
+}
+
+#[test]
+fn removals_are_valid_and_minimal() {
+    let mut rng = Rng::new(2024);
+    let mut safe = 0;
+    for _ in 0..3000 {
+        let report = report(&mut rng);
+        let steps = [1..=3, 0..=3, 0..=1, 2..=4][rng.below(4)].clone();
+        let rules = SafetyRules { steps, max_removals: rng.between(0, 3) };
+        let removed = rules.make_safe(&report);
+        let context = format!("{:?} with {:?} gives {:?}", report, rules, removed);
+        assert_eq!(removed.is_some(), rules.is_safe_by_brute_force(&report), "{}", context);
+        let Some(removed) = removed else { continue };
+        safe += 1;
+
+        assert!(removed.windows(2).all(|pair| pair[0] < pair[1]), "{}", context);
+        assert!(removed.iter().all(|idx| *idx < report.len()), "{}", context);
+        let kept = report.iter()
+            .enumerate()
+            .filter(|(idx, _)| !removed.contains(idx))
+            .map(|(_, level)| *level)
+            .collect::<Vec<i32>>();
+        assert!(rules.is_safe(&kept), "{} keeps {:?}", context, kept);
+        assert_eq!(Some(removed.len()), fewest_removals(&report, &rules), "{}", context);
+    }
+    assert!(safe > 500, "only {} safe reports, the generator needs tuning", safe);
+}
+
+/// With steps of 0 allowed, a flat stretch fits a report going either way.
+#[test]
+fn flat_steps_go_either_way() {
+    let rules = SafetyRules { steps: 0..=3, max_removals: 0 };
+    for report in [[5, 5, 4], [5, 5, 6], [4, 4, 4]] {
+        assert!(rules.is_safe(&report), "{:?}", report);
+        assert!(rules.is_safe_by_brute_force(&report), "{:?}", report);
+        assert_eq!(rules.make_safe(&report), Some(vec![]), "{:?}", report);
+    }
+    assert!(!rules.is_safe(&[5, 5, 4, 5]));
+    assert_eq!(SafetyRules { max_removals: 1, ..rules }.make_safe(&[5, 5, 4, 5]).map(|removed| removed.len()), Some(1));
+}
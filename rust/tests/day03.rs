@@ -0,0 +1,17 @@
+use rust::day03::{Instruction, Interpreter, Scanner, Syntax, MUL, SYNTAX};
+
+#[test]
+fn scanner_finds_only_well_formed_calls() {
+    let source = "mul(1,2)mul(1234,5)mul( 1,2)don't()mul(3,4)";
+    let instructions = Scanner::new(source, &SYNTAX).map(|token| token.instruction).collect::<Vec<Instruction>>();
+    assert_eq!(instructions, vec![Instruction::Mul(1, 2), Instruction::Dont, Instruction::Mul(3, 4)]);
+    assert_eq!(Interpreter::new(true).run(source, &SYNTAX), 2);
+}
+
+#[test]
+fn extensions_run_declared_instructions() {
+    let syntax = [MUL, Syntax { name: "add", arity: 2 }];
+    let mut interpreter = Interpreter::new(false);
+    interpreter.define("add", |interpreter, args| interpreter.total += args[0] + args[1]);
+    assert_eq!(interpreter.run("add(1,2)mul(3,4)", &syntax), 15);
+}
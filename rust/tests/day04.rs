@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+use rust::day04::{Day04, Grid, Stencil, StencilMatch, WordMatch, V2};
+use rust::DaySolution;
+
+fn grid(raw: &str) -> Grid {
+    Day04::new().parse_input(raw)
+}
+
+fn word(start: (i16, i16), direction: (i16, i16)) -> WordMatch {
+    WordMatch { start: V2(start.0, start.1), direction: V2(direction.0, direction.1) }
+}
+
+fn cells(found: &WordMatch, len: i16) -> Vec<V2> {
+    (0..len).map(|i| V2(found.start.0 + i * found.direction.0, found.start.1 + i * found.direction.1)).collect()
+}
+
+#[test]
+fn words_are_found_in_every_direction() {
+    let grid = grid("XMAS\nMM..\nA.A.\nS..S\n");
+    assert_eq!(grid.find_word("XMAS", true), vec![word((0, 0), (0, 1)), word((0, 0), (1, 0)), word((0, 0), (1, 1))]);
+    assert_eq!(grid.find_word("SAMX", true), vec![word((3, 0), (-1, 0)), word((0, 3), (0, -1)), word((3, 3), (-1, -1))]);
+    assert_eq!(grid.find_word("XMAS", false), vec![word((0, 0), (0, 1))]);
+}
+
+#[test]
+fn without_overlaps_each_cell_is_used_once() {
+    let grid = grid("XMASAMX\nMMMMMMM\nAAAAAAA\nSSSSSSS\n");
+    let all = grid.find_word("XMAS", true);
+    let disjoint = grid.find_word("XMAS", false);
+    assert_eq!(all.len(), 6);
+    assert_eq!(disjoint, vec![word((0, 0), (0, 1)), word((6, 0), (0, 1))]);
+
+    let used = disjoint.iter().flat_map(|found| cells(found, 4)).collect::<Vec<V2>>();
+    assert_eq!(used.iter().collect::<HashSet<_>>().len(), used.len());
+}
+
+/// `ABA` read east from (0, 0) and west from (2, 0) covers the same cells, so it counts once.
+#[test]
+fn palindromes_count_once() {
+    assert_eq!(grid("ABA\n").find_word("ABA", true), vec![word((0, 0), (1, 0))]);
+    assert_eq!(grid("ABA\nB..\nA..\n").find_word("ABA", true), vec![word((0, 0), (0, 1)), word((0, 0), (1, 0))]);
+    assert_eq!(grid("ABA\n.B.\n..A\n").find_word("ABA", true).len(), 2);
+    assert_eq!(grid("AB\nBA\n").find_word("A", true), vec![word((0, 0), (0, 1)), word((1, 1), (0, 1))]);
+}
+
+#[test]
+fn variants_are_distinct_rotations_and_reflections() {
+    let single = Stencil::parse("A");
+    assert_eq!(single.variants(), vec![single]);
+
+    let pair = Stencil::parse("AB");
+    let pair_variants = pair.variants();
+    assert_eq!(pair_variants.len(), 4);
+    for rotated in ["A\nB", "BA", "B\nA"] {
+        assert!(pair_variants.contains(&Stencil::parse(rotated)), "{}", rotated);
+    }
+
+    let x_mas = Stencil::parse("M.S\n.A.\nM.S");
+    assert_eq!(x_mas.variants().len(), 4, "its mirror image is one of its rotations");
+
+    let corner = Stencil::parse("AB\nC.");
+    let corner_variants = corner.variants();
+    assert_eq!(corner_variants[0], corner);
+    assert_eq!(corner_variants.len(), 8);
+    assert!(corner_variants.contains(&Stencil::parse("BA\n.C")));
+    for (i, variant) in corner_variants.iter().enumerate() {
+        assert!(!corner_variants[i + 1..].contains(variant), "{:?} twice", variant);
+    }
+}
+
+#[test]
+fn stencils_match_with_wildcards_and_variants() {
+    let x_mas = Stencil::parse("M.S\n.A.\nM.S");
+    assert_eq!(grid("MXS\nXAX\nMXS\n").find_stencil(&x_mas, false), vec![StencilMatch { origin: V2(0, 0), variant: 0 }]);
+
+    let rotated = grid("..S.S\n...A.\n..M.M\n");
+    assert_eq!(rotated.find_stencil(&x_mas, false), vec![]);
+    let found = rotated.find_stencil(&x_mas, true);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].origin, V2(2, 0));
+    assert_eq!(x_mas.variants()[found[0].variant], Stencil::parse("S.S\n.A.\nM.M"));
+}
@@ -0,0 +1,42 @@
+use std::collections::HashSet;
+use rust::DaySolution;
+use rust::day06::{Day06, Op, Position};
+
+const EXAMPLE: &str = "....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^.....\n........#.\n#.........\n......#...\n";
+
+#[test]
+fn walk_turns_at_obstacles() {
+    let mut day = Day06::new();
+    let data = day.parse_input(".#.\n...\n.^.\n");
+    let steps = data.walk().collect::<Vec<_>>();
+    assert_eq!(steps[0].op, Op::Forward);
+    assert_eq!(steps[0].guard.position, Position { x: 1, y: 1 });
+    assert_eq!(steps[1].op, Op::TurnRight);
+    assert_eq!(steps.last().unwrap().guard.position, Position { x: 2, y: 1 });
+}
+
+/// Every cell of the path but the start, once each, among them the six that make a loop.
+#[test]
+fn candidates_on_the_example_leave_out_the_start() {
+    let mut day = Day06::new();
+    let data = day.parse_input(EXAMPLE);
+    let candidates = data.obstruction_candidates().into_iter().map(|(_, position)| position).collect::<Vec<Position>>();
+    let unique = candidates.iter().copied().collect::<HashSet<Position>>();
+    assert_eq!(candidates.len(), 40);
+    assert_eq!(unique.len(), candidates.len());
+    assert!(!unique.contains(&data.start().position));
+    let loops = [(3, 6), (6, 7), (7, 7), (1, 8), (3, 8), (7, 9)];
+    assert!(loops.iter().all(|&(x, y)| unique.contains(&Position { x, y })));
+    assert_eq!(day.part2(&data), loops.len());
+}
+
+#[test]
+fn candidates_leave_out_the_start_when_the_path_crosses_it() {
+    let mut day = Day06::new();
+    let data = day.parse_input(".#..\n...#\n.^..\n..#.\n");
+    let start = data.start().position;
+    assert!(data.walk().any(|step| step.op == Op::Forward && step.guard.position == start));
+    let candidates = data.obstruction_candidates();
+    assert!(candidates.iter().all(|(_, position)| *position != start));
+    assert_eq!(candidates.len(), 4);
+}
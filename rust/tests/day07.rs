@@ -0,0 +1,87 @@
+use rust::day07::{Evaluation, Op, Operator, OperatorSet, Problem, Undo, OPS_1, OPS_2};
+
+#[test]
+fn equations_show_the_operators() {
+    let set = OperatorSet::new(&OPS_1, Evaluation::LeftToRight);
+    let equation = Problem::from("292: 11 6 16 20").solve(&set).unwrap();
+    assert_eq!(equation.to_string(), "292 = 11 + 6 * 16 + 20");
+    assert!(Problem::from("83: 17 5").solve(&set).is_none());
+}
+
+#[test]
+fn precedence_binds_tighter_operators_first() {
+    let evaluate = |numbers: &[i128], ops: &[&dyn Operator]| Evaluation::Precedence.evaluate(numbers, ops);
+    assert_eq!(evaluate(&[2, 3, 4], &[&Op::Add, &Op::Mul]), Some(14));
+    assert_eq!(evaluate(&[2, 3, 4], &[&Op::Mul, &Op::Add]), Some(10));
+    assert_eq!(evaluate(&[10, 3, 2], &[&Op::Sub, &Op::Sub]), Some(5));
+    assert_eq!(evaluate(&[12, 1, 3], &[&Op::Concat, &Op::Add]), Some(124));
+    assert_eq!(Evaluation::LeftToRight.evaluate(&[2, 3, 4], &[&Op::Add, &Op::Mul]), Some(20));
+}
+
+#[test]
+fn powers_group_from_the_right() {
+    let evaluate = |numbers: &[i128], ops: &[&dyn Operator]| Evaluation::Precedence.evaluate(numbers, ops);
+    assert_eq!(evaluate(&[2, 3, 3], &[&Op::Pow, &Op::Pow]), Some(134217728));
+    assert_eq!(evaluate(&[2, 2, 3, 2], &[&Op::Mul, &Op::Pow, &Op::Pow]), Some(1024));
+    let equation = Problem::from("134217728: 2 3 3").solve(&OperatorSet::new(&[&Op::Pow], Evaluation::Precedence)).unwrap();
+    assert_eq!(equation.to_string(), "134217728 = 2 ** 3 ** 3");
+    assert!(Problem::from("512: 2 3 3").solve(&OperatorSet::new(&[&Op::Pow], Evaluation::Precedence)).is_none());
+}
+
+#[test]
+fn precedence_changes_which_equations_hold() {
+    let set = OperatorSet::new(&OPS_1, Evaluation::Precedence);
+    assert_eq!(Problem::from("14: 2 3 4").solve(&set).unwrap().to_string(), "14 = 2 + 3 * 4");
+    assert!(Problem::from("20: 2 3 4").solve(&set).is_none());
+    let set = OperatorSet::new(&OPS_2, Evaluation::Precedence);
+    assert_eq!(Problem::from("156: 15 6").solve(&set).unwrap().to_string(), "156 = 15 || 6");
+}
+
+/// Subtraction reaches negative intermediate values, so they must not be pruned.
+#[test]
+fn backward_search_keeps_negative_targets_when_reachable() {
+    let set = OperatorSet::new(&[&Op::Add, &Op::Sub], Evaluation::LeftToRight);
+    assert_eq!(Problem::from("5: 3 8 10").solve(&set).unwrap().to_string(), "5 = 3 - 8 + 10");
+}
+
+/// The only overflowing division: `i128::MIN / -1`.
+#[test]
+fn undoing_a_product_does_not_overflow() {
+    assert_eq!(Op::Mul.undo(i128::MIN, -1), Undo::Impossible);
+    assert_eq!(Op::Mul.undo(i128::MIN, 2), Undo::Operand(i128::MIN / 2));
+    let set = OperatorSet::new(&[&Op::Sub, &Op::Mul], Evaluation::LeftToRight);
+    let equation = Problem::from(format!("{}: 1 -1", i128::MIN).as_str()).solve(&set);
+    assert!(equation.is_none());
+}
+
+#[test]
+#[should_panic(expected = "no numbers")]
+fn equations_need_numbers() {
+    let _ = Problem::from("5: ");
+}
+
+/// An operator without an inverse, which the solver has to try forwards.
+struct Max;
+
+impl Operator for Max {
+    fn symbol(&self) -> &str {
+        "max"
+    }
+
+    fn precedence(&self) -> u8 {
+        5
+    }
+
+    fn apply(&self, a: i128, b: i128) -> Option<i128> {
+        Some(a.max(b))
+    }
+}
+
+#[test]
+fn custom_operators_take_part_in_the_search() {
+    let set = OperatorSet::new(&[&Op::Add, &Max], Evaluation::LeftToRight);
+    assert_eq!(Problem::from("12: 3 7 5").solve(&set).unwrap().to_string(), "12 = 3 max 7 + 5");
+    assert!(Problem::from("4: 3 7 5").solve(&set).is_none());
+    let set = OperatorSet::new(&[&Op::Mul, &Max], Evaluation::Precedence);
+    assert_eq!(Problem::from("14: 2 3 7").solve(&set).unwrap().to_string(), "14 = 2 * 3 max 7");
+}
@@ -0,0 +1,71 @@
+use std::collections::HashSet;
+use rust::day08::{Day08, Mode, Position};
+use rust::{puzzle, DaySolution, Part};
+
+fn positions(points: &[(i32, i32)]) -> HashSet<Position> {
+    points.iter().map(|&(x, y)| Position::new(x, y)).collect()
+}
+
+#[test]
+fn resonance_keeps_points_between_antennas() {
+    let mut day = Day08::new();
+    let data = day.parse_input("a....\n.....\n..a..\n.....\n.....\n.....\n.....\n");
+    assert_eq!(data.antinode_report(Mode::Resonance).all(), positions(&[(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)]));
+    assert_eq!(data.antinode_report(Mode::LiteralResonance).all(), positions(&[(0, 0), (2, 2), (4, 4)]));
+    assert_eq!(data.antinode_report(Mode::Pair).all(), positions(&[(4, 4)]));
+}
+
+/// A distance of (4, 2) steps by (2, 1), a distance of (3, 1) cannot be reduced.
+#[test]
+fn resonance_steps_by_the_reduced_distance() {
+    let mut day = Day08::new();
+    let data = day.parse_input("b.......\n........\n....b...\n........\n........\n");
+    assert_eq!(data.antinode_report(Mode::Resonance).all(), positions(&[(0, 0), (2, 1), (4, 2), (6, 3)]));
+    assert_eq!(data.antinode_report(Mode::LiteralResonance).all(), positions(&[(0, 0), (4, 2)]));
+
+    let data = day.parse_input("........\n.c......\n....c...\n........\n");
+    assert_eq!(data.antinode_report(Mode::Resonance).all(), positions(&[(1, 1), (4, 2), (7, 3)]));
+    assert_eq!(data.antinode_report(Mode::Pair).all(), positions(&[(7, 3)]));
+}
+
+#[test]
+fn antennas_on_a_row_resonate_along_it() {
+    let mut day = Day08::new();
+    let data = day.parse_input("......\nd.d...\n");
+    assert_eq!(data.antinode_report(Mode::Resonance).all(), positions(&[(0, 1), (1, 1), (2, 1), (3, 1), (4, 1), (5, 1)]));
+    assert_eq!(data.antinode_report(Mode::Pair).all(), positions(&[(4, 1)]));
+}
+
+#[test]
+fn literal_resonance_is_a_part2_strategy() {
+    let input = "b.......\n........\n....b...\n........\n........\n";
+    let mut day = puzzle(8).unwrap();
+    let data = day.parse_input(input);
+    assert_eq!(day.solve(data.as_ref(), Part::Two).as_str(), "4");
+    assert!(day.use_strategy(Part::Two, "literal"));
+    assert_eq!(day.solve(data.as_ref(), Part::Two).as_str(), "2");
+    assert!(!day.use_strategy(Part::One, "literal"));
+}
+
+/// `a` and `b` both put a pair antinode in the bottom right corner.
+#[test]
+fn overlaps_count_antinodes_shared_by_frequencies() {
+    let mut day = Day08::new();
+    let data = day.parse_input("a.b\n.ab\n...\n");
+    let pair = data.antinode_report(Mode::Pair);
+    assert_eq!(pair.by_frequency[&'a'], positions(&[(2, 2)]));
+    assert_eq!(pair.overlaps().into_iter().collect::<Vec<_>>(), vec![(('a', 'b'), 1)]);
+    assert_eq!(pair.describe_overlaps(), "Shared by a and b: 1\n");
+
+    let data = day.parse_input("a..\n...\n..b\n");
+    assert!(data.antinode_report(Mode::Pair).overlaps().is_empty());
+    assert_eq!(data.antinode_report(Mode::Pair).describe_overlaps(), "No antinode shared between frequencies\n");
+}
+
+#[test]
+fn render_lists_the_overlaps() {
+    let mut day = Day08::new();
+    let data = day.parse_input("a.b\n.ab\n...\n");
+    let render = day.render(&data).unwrap();
+    assert!(render.starts_with("Part 1 antinodes:\na.b\n.ab\n..#\nShared by a and b: 1\n"), "{}", render);
+}
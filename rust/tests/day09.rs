@@ -0,0 +1,43 @@
+use rust::day09::{compact_by_brute_force, BestFit, BlockWise, Disk, WholeFile};
+
+/// In the example the leftmost gap a file fits is also the smallest, so best fit moves whole
+/// files to the same places.
+#[test]
+fn strategies_compact_the_example() {
+    let disk = Disk::from_map("2333133121414131402");
+    assert_eq!(disk.compact(&BlockWise).render(), "0099811188827773336446555566..............");
+    assert_eq!(disk.compact(&WholeFile).render(), "00992111777.44.333....5555.6666.....8888..");
+    assert_eq!(disk.compact(&BlockWise).checksum(), 1928);
+    assert_eq!(disk.compact(&WholeFile).checksum(), 2858);
+    assert_eq!(disk.compact(&BestFit).render(), "00992111777.44.333....5555.6666.....8888..");
+    assert_eq!(disk.compact(&BestFit).checksum(), 2858);
+}
+
+/// A file of no blocks merges the free spans around it into one of 14 blocks.
+#[test]
+fn free_spans_longer_than_a_digit_are_indexed() {
+    let disk = Disk::from_map("1905");
+    assert_eq!(disk.render(), "0..............");
+    for compacted in [disk.compact(&WholeFile), disk.compact(&BestFit)] {
+        assert_eq!(compacted.blocks(), compact_by_brute_force(&disk, true));
+    }
+    let disk = Disk::from_map("19052");
+    assert_eq!(disk.compact(&WholeFile).render(), "022..............");
+}
+
+#[test]
+fn compacted_disks_stay_as_they_are() {
+    let disk = Disk::from_map("2333133121414131402");
+    let compacted = disk.compact(&BlockWise);
+    assert_eq!(compacted.compact(&WholeFile), compacted);
+    assert_eq!(compacted.compact(&BestFit), compacted);
+    assert_eq!(compacted.compact(&BlockWise), compacted);
+}
+
+/// File 2 fits the leftmost gap of 3 blocks, or the single block right after file 1.
+#[test]
+fn best_fit_picks_the_smallest_gap() {
+    let disk = Disk::from_map("13111");
+    assert_eq!(disk.compact(&WholeFile).render(), "021....");
+    assert_eq!(disk.compact(&BestFit).render(), "01...2.");
+}
@@ -0,0 +1,51 @@
+use rust::day10::{Day10, Position, TrailRules};
+use rust::DaySolution;
+
+const EXAMPLE: &str = "89010123\n78121874\n87430965\n96549874\n45678903\n32019012\n01329801\n10456732\n";
+
+#[test]
+fn blank_cells_break_trails() {
+    let mut day = Day10::new();
+    let data = day.parse_input("...0...\n...1...\n...2...\n6543456\n7.....7\n8.....8\n9.....9\n");
+    assert_eq!(day.part1(&data), 2);
+    assert_eq!(day.part2(&data), 2);
+    let data = day.parse_input("0123\n...4\n9875\n..6.\n");
+    assert_eq!(day.part2(&data), 0);
+}
+
+/// Walking down from the summits finds the same trails backwards, and the same pairs of head
+/// and summit.
+#[test]
+fn custom_rules_reach_the_scoring() {
+    let mut up = Day10::new();
+    let mut down = Day10::with_rules(TrailRules { start: 9, end: 0, step: -1 });
+    let data = up.parse_input(EXAMPLE);
+    assert_eq!((up.part1(&data), up.part2(&data)), (36, 81));
+    assert_eq!(down.part2(&data), 81);
+    assert_eq!(down.part1(&data), 36);
+
+    let mut even = Day10::with_rules(TrailRules { start: 0, end: 4, step: 2 });
+    let data = even.parse_input("024\n242\n420\n");
+    assert_eq!(even.part1(&data), 6);
+    assert_eq!(even.part2(&data), 8);
+}
+
+#[test]
+fn trails_are_listed_from_their_head() {
+    let mut day = Day10::new();
+    let data = day.parse_input("0123\n1234\n8765\n9876\n");
+    let rules = TrailRules::default();
+    assert_eq!(data.trail_heads(&rules).collect::<Vec<Position>>(), vec![Position::new(0, 0)]);
+    let trails = data.trails_from(Position::new(0, 0), &rules);
+    assert_eq!(trails.len(), 16);
+    assert!(trails.iter().all(|trail| trail.len() == 10 && trail[9] == Position::new(0, 3)));
+    assert!(data.trails_from(Position::new(1, 0), &rules).is_empty());
+    assert_eq!(data.render(&trails[..1]), "0123\n...4\n8765\n9...\n");
+}
+
+#[test]
+fn render_names_the_head_by_its_coordinates() {
+    let mut day = Day10::new();
+    let data = day.parse_input(EXAMPLE);
+    assert!(day.render(&data).unwrap().starts_with("20 trails from (2, 0):\n"));
+}
@@ -0,0 +1,46 @@
+use std::collections::HashSet;
+use rust::day11::{blink_by_brute_force, simulate, BlinkError, Day11, Engine};
+use rust::DaySolution;
+
+#[test]
+fn engines_agree() {
+    let stones = vec![125, 17];
+    let mut memo = Day11::with_engine(Engine::Memo);
+    let mut count_map = Day11::with_engine(Engine::CountMap);
+    assert_eq!(memo.part1(&stones), 55312);
+    assert_eq!(memo.part2(&stones), count_map.part2(&stones));
+}
+
+/// Both engines stop at the first blink that cannot represent a stone.
+#[test]
+fn engines_report_overflowing_stones() {
+    let stone = u64::MAX / 1000;
+    let overflow = Err(BlinkError::StoneOverflow { blink: 1, stone });
+    assert_eq!(simulate(&[stone], 1).map(|report| report.total), overflow);
+    for engine in [Engine::Memo, Engine::CountMap] {
+        assert_eq!(Day11::with_engine(engine).count(engine, &[stone], 1), overflow, "{:?}", engine);
+    }
+    // 500000000000000 becomes 1012000000000000000 first, which overflows at the second blink.
+    for engine in [Engine::Memo, Engine::CountMap] {
+        let error = Day11::with_engine(engine).count(engine, &[500_000_000_000_000], 2).unwrap_err();
+        assert_eq!(error, BlinkError::StoneOverflow { blink: 2, stone: 1_012_000_000_000_000_000 }, "{:?}", engine);
+    }
+}
+
+#[test]
+fn distinct_stones_match_literal_blinks() {
+    let stones = [0, 1, 10, 99, 999, 125, 17];
+    let report = simulate(&stones, 8).unwrap();
+    assert_eq!(report.distinct.len(), 9);
+    for (blinks, distinct) in report.distinct.iter().enumerate() {
+        let row = blink_by_brute_force(&stones, blinks);
+        assert_eq!(*distinct, row.iter().collect::<HashSet<_>>().len(), "after {} blinks", blinks);
+    }
+}
+
+#[test]
+fn strategies_name_the_engines() {
+    let day = Day11::new();
+    assert_eq!(day.strategies(rust::Part::One), [Engine::CountMap.name(), Engine::Memo.name(), Engine::Literal.name()]);
+    assert_eq!(day.strategies(rust::Part::Two), [Engine::CountMap.name(), Engine::Memo.name()]);
+}
@@ -0,0 +1,138 @@
+//! Runs the fast solvers against the brute-force references on many small random inputs. Inputs
+//! are tried from the smallest up, so a failure shows the smallest input found to disagree.
+
+use std::fmt::Debug;
+use rust::{DaySolution, Part};
+use rust::generate::{generate, Knobs, Rng};
+use rust::day05::Day05;
+use rust::day10::{Day10, TrailRules};
+use rust::day09::{compact_by_brute_force, BestFit, BlockWise, Disk, WholeFile};
+use rust::day11::{blink_by_brute_force, simulate, Day11, Engine};
+
+const CASES_PER_SIZE: usize = 200;
+
+/// Generates inputs of each size in `sizes`, smallest first, and panics on the first one where
+/// `agree` returns an explanation of the mismatch.
+fn check<T: Debug>(sizes: std::ops::RangeInclusive<usize>, generate: impl Fn(&mut Rng, usize) -> T, agree: impl Fn(&T) -> Result<(), String>) {
+    let mut rng = Rng::new(0);
+    for size in sizes {
+        for _ in 0..CASES_PER_SIZE {
+            let input = generate(&mut rng, size);
+            if let Err(mismatch) = agree(&input) {
+                panic!("smallest disagreeing input (size {}) :: {:?}\n{}", size, input, mismatch);
+            }
+        }
+    }
+}
+
+fn disk_map(rng: &mut Rng, files: usize) -> String {
+    let knobs = Knobs { seed: rng.next_u64(), count: files * 2 - 1, width: 0, height: 0, density: 0.0 };
+    generate(9, &knobs).unwrap().trim().to_string()
+}
+
+#[test]
+fn day09_block_wise_matches_block_moves() {
+    check(1..=12, disk_map, |map| {
+        let disk = Disk::from_map(map);
+        let fast = disk.compact(&BlockWise).blocks();
+        let reference = compact_by_brute_force(&disk, false);
+        if fast == reference { Ok(()) } else { Err(format!("fast      {:?}\nreference {:?}", fast, reference)) }
+    });
+}
+
+#[test]
+fn day09_whole_file_matches_block_moves() {
+    check(1..=12, disk_map, |map| {
+        let disk = Disk::from_map(map);
+        let fast = disk.compact(&WholeFile).blocks();
+        let reference = compact_by_brute_force(&disk, true);
+        if fast == reference { Ok(()) } else { Err(format!("fast      {:?}\nreference {:?}", fast, reference)) }
+    });
+}
+
+#[test]
+fn day09_best_fit_keeps_every_block() {
+    check(1..=12, disk_map, |map| {
+        let disk = Disk::from_map(map);
+        let mut before = disk.blocks().into_iter().flatten().collect::<Vec<usize>>();
+        let mut after = disk.compact(&BestFit).blocks().into_iter().flatten().collect::<Vec<usize>>();
+        before.sort();
+        after.sort();
+        if before == after { Ok(()) } else { Err(format!("before {:?}\nafter  {:?}", before, after)) }
+    });
+}
+
+fn stones(rng: &mut Rng, count: usize) -> Vec<u64> {
+    let knobs = Knobs { seed: rng.next_u64(), count, width: 0, height: 0, density: 0.0 };
+    generate(11, &knobs).unwrap().split_whitespace().map(|stone| stone.parse().unwrap()).collect()
+}
+
+#[test]
+fn day11_engines_match_literal_blinks() {
+    check(1..=4, stones, |stones| {
+        let mut memo = Day11::with_engine(Engine::Memo);
+        for blinks in 0..=12 {
+            let reference = blink_by_brute_force(stones, blinks).len() as u128;
+            let memo = memo.count(Engine::Memo, stones, blinks).unwrap();
+            let count_map = simulate(stones, blinks).unwrap().total;
+            if memo != reference || count_map != reference {
+                return Err(format!("{} blinks :: memo {}, count map {}, literal {}", blinks, memo, count_map, reference));
+            }
+        }
+        Ok(())
+    });
+}
+
+/// A print queue over `pages` pages, with a rule for every pair of them as the puzzle inputs do.
+fn print_queue(rng: &mut Rng, pages: usize) -> String {
+    let knobs = Knobs { seed: rng.next_u64(), count: 4, width: pages, height: 0, density: 0.0 };
+    generate(5, &knobs).unwrap()
+}
+
+#[test]
+fn day05_comparator_sort_matches_permutations() {
+    check(2..=6, print_queue, |input| {
+        let data = Day05::new().parse_input(input);
+        let updates = input.split("\n\n").nth(1).unwrap().lines();
+        for update in updates.map(|line| line.split(',').map(|page| page.parse().unwrap()).collect::<Vec<i32>>()) {
+            if data.is_sorted(&update) != data.is_sorted_by_brute_force(&update) {
+                return Err(format!("{:?} :: sorted {}", update, data.is_sorted(&update)));
+            }
+            let fast = data.reorder(&update);
+            let reference = data.reorder_by_brute_force(&update);
+            if Some(&fast) != reference.as_ref() {
+                return Err(format!("{:?} :: fast {:?}, reference {:?}", update, fast, reference));
+            }
+        }
+        Ok(())
+    });
+}
+
+fn topographic_map(rng: &mut Rng, side: usize) -> String {
+    let knobs = Knobs { seed: rng.next_u64(), count: side * side / 8 + 1, width: side, height: side, density: 0.0 };
+    generate(10, &knobs).unwrap()
+}
+
+#[test]
+fn day10_walks_and_ratings_match_trail_listing() {
+    check(1..=12, topographic_map, |input| {
+        for rules in [TrailRules::default(), TrailRules { start: 9, end: 0, step: -1 }, TrailRules { start: 1, end: 7, step: 2 }] {
+            for part in [Part::One, Part::Two] {
+                let answer = |strategy| {
+                    let mut day = Day10::with_rules(rules);
+                    day.use_strategy(part, strategy);
+                    let data = day.parse_input(input);
+                    match part {
+                        Part::One => day.part1(&data),
+                        Part::Two => day.part2(&data),
+                    }
+                };
+                let (dp, dfs) = (answer("dp"), answer("dfs"));
+                if dp != dfs {
+                    return Err(format!("{:?} {} :: dp {}, dfs {}", rules, part, dp, dfs));
+                }
+            }
+        }
+        Ok(())
+    });
+}
@@ -0,0 +1,79 @@
+//! Runs every strategy of every registered day on the inputs in `data/` and compares with the
+//! `.answer` file next to each one: the part 1 answer on the first line, the part 2 answer on the
+//! second, `-` to leave a part unchecked.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use rust::{puzzle, puzzles, Part};
+
+fn data_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../data")
+}
+
+/// The inputs of `day` whose name starts with `prefix`, e.g. `day03-ex` also finds `day03-ex2`.
+fn inputs(day: u16, prefix: &str) -> Vec<PathBuf> {
+    let prefix = format!("day{:02}-{}", day, prefix);
+    let mut inputs = fs::read_dir(data_dir())
+        .map(|entries| {
+            entries
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| {
+                    let name = path.file_name().unwrap().to_string_lossy();
+                    name.starts_with(&prefix) && name.ends_with(".input")
+                })
+                .collect()
+        })
+        .unwrap_or_else(|_| vec![]);
+    inputs.sort();
+    inputs
+}
+
+/// Checks both parts of every input found, returns the mismatches and how many inputs were run.
+fn check(prefix: &str) -> (Vec<String>, usize) {
+    let mut failures = vec![];
+    let mut checked = 0;
+    for day in puzzles().iter().map(|puzzle| puzzle.day()) {
+        for input in inputs(day, prefix) {
+            let answers = input.with_extension("answer");
+            let Ok(expected) = fs::read_to_string(&answers) else {
+                failures.push(format!("{} has no {}", input.display(), answers.display()));
+                continue;
+            };
+            let raw_data = fs::read_to_string(&input).unwrap();
+            for (part, expected) in [Part::One, Part::Two].into_iter().zip(expected.lines()) {
+                let expected = expected.trim();
+                if expected == "-" {
+                    continue;
+                }
+                for strategy in puzzle(day).unwrap().strategies(part) {
+                    let mut puzzle = puzzle(day).unwrap();
+                    puzzle.use_strategy(part, strategy);
+                    let data = puzzle.parse_input(&raw_data);
+                    let answer = puzzle.solve(data.as_ref(), part);
+                    if answer.as_str() != expected {
+                        failures.push(format!("{} {} [{}] :: expected {}, got {}", input.display(), part, strategy, expected, answer));
+                    }
+                }
+            }
+            checked += 1;
+        }
+    }
+    (failures, checked)
+}
+
+#[test]
+fn examples() {
+    let (failures, checked) = check("ex");
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    assert!(checked >= puzzles().len(), "only {} examples found", checked);
+}
+
+/// Puzzle inputs may not be shared, so a clone without them skips this test instead of failing.
+#[test]
+fn puzzle_inputs() {
+    let (failures, checked) = check("p1");
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    if checked == 0 {
+        eprintln!("no puzzle input in {}, skipped", data_dir().display());
+    }
+}
@@ -0,0 +1,43 @@
+use rust::generate::{generate, Knobs};
+use rust::{puzzles, Part};
+
+fn small(day: u16, seed: u64) -> Knobs {
+    let official = Knobs::official(day);
+    Knobs {
+        seed,
+        count: official.count.min(40),
+        width: official.width.min(12),
+        height: official.height.min(12),
+        ..official
+    }
+}
+
+#[test]
+fn every_day_solves_its_generated_inputs() {
+    for mut puzzle in puzzles() {
+        for seed in 0..20 {
+            let input = generate(puzzle.day(), &small(puzzle.day(), seed)).expect("a generator for every day");
+            let data = puzzle.parse_input(&input);
+            puzzle.solve(data.as_ref(), Part::One);
+            puzzle.solve(data.as_ref(), Part::Two);
+        }
+    }
+}
+
+#[test]
+fn seeds_give_the_same_input() {
+    let knobs = small(8, 42);
+    assert_eq!(generate(8, &knobs), generate(8, &knobs));
+    assert_ne!(generate(8, &knobs), generate(8, &Knobs { seed: 43, ..knobs.clone() }));
+    assert_eq!(generate(12, &knobs), None);
+}
+
+#[test]
+fn knobs_set_the_size() {
+    let knobs = Knobs { seed: 1, count: 5, width: 7, height: 3, density: 0.0 };
+    let grid = generate(4, &knobs).unwrap();
+    assert_eq!(grid.lines().map(|line| line.len()).collect::<Vec<usize>>(), vec![7, 7, 7]);
+    assert_eq!(generate(1, &knobs).unwrap().lines().count(), 5);
+    assert_eq!(generate(9, &knobs).unwrap().trim().len(), 5);
+    assert!(!generate(6, &knobs).unwrap().contains('#'));
+}
@@ -0,0 +1,45 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use rust::memory::{self, Usage};
+
+/// Measurements share the counters, and each one resets the peak, so tests that measure take
+/// turns.
+static MEASURING: Mutex<()> = Mutex::new(());
+
+fn measuring() -> MutexGuard<'static, ()> {
+    MEASURING.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[test]
+fn counts_allocations_when_enabled() {
+    let _measuring = measuring();
+    let (sum, usage) = memory::measure(|| {
+        let numbers = (0..1000_u64).collect::<Vec<u64>>();
+        numbers.iter().sum::<u64>()
+    });
+    assert_eq!(sum, 499500);
+    if memory::enabled() {
+        assert!(usage.allocations >= 1);
+        assert!(usage.bytes >= 8000);
+        assert!(usage.peak >= 8000);
+    } else {
+        assert_eq!(usage, Usage::default());
+    }
+}
+
+#[test]
+fn sizes_read_in_binary_units() {
+    assert_eq!(memory::human(0), "0B");
+    assert_eq!(memory::human(1023), "1023B");
+    assert_eq!(memory::human(1536), "1.5KiB");
+    assert_eq!(memory::human(3 << 20), "3.0MiB");
+    assert_eq!(memory::human(5 << 30), "5.0GiB");
+}
+
+/// A measurement taken inside another one counts in both, and both say so.
+#[test]
+fn overlapping_measurements_are_flagged() {
+    let _measuring = measuring();
+    let ((_, inner), outer) = memory::measure(|| memory::measure(|| vec![0_u8; 64]));
+    assert_eq!(inner.overlapped, memory::enabled());
+    assert_eq!(outer.overlapped, memory::enabled());
+}
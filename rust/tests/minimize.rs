@@ -0,0 +1,60 @@
+use rust::minimize::{disagrees_with_reference, minimize, reduce, MinimizeError, Predicate};
+use rust::Part;
+
+#[test]
+fn reduce_keeps_only_what_the_failure_needs() {
+    let input = "abc\nxde\nfgy\nhij\n";
+    assert_eq!(reduce(input, |candidate| candidate.contains('x') && candidate.contains('y')), "xy");
+}
+
+#[test]
+fn reduce_drops_grid_columns_together() {
+    let grid = "....\n.#..\n....\n";
+    let minimized = reduce(grid, |candidate| {
+        let widths = candidate.lines().map(|line| line.len()).collect::<Vec<usize>>();
+        candidate.contains('#') && widths.windows(2).all(|pair| pair[0] == pair[1])
+    });
+    assert_eq!(minimized, "#");
+}
+
+#[test]
+fn minimize_shrinks_a_mismatching_answer() {
+    let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+    // A claimed answer of 0 fails on any pair of lists that differ.
+    let minimized = minimize(1, input, &Predicate::Mismatch(Part::One, "0".to_string())).unwrap();
+    assert!(minimized.len() < input.len());
+    assert_ne!(rust::solve(2024, 1, Part::One, &minimized).unwrap().as_str(), "0");
+}
+
+#[test]
+fn minimize_needs_a_failing_input() {
+    let input = "3   4\n4   3\n";
+    assert_eq!(minimize(1, input, &Predicate::Mismatch(Part::One, "0".to_string())), Err(MinimizeError::NotFailing));
+    assert_eq!(minimize(1, input, &Predicate::Panics(Part::One)), Err(MinimizeError::NotFailing));
+    assert_eq!(minimize(1, input, &Predicate::Reference), Err(MinimizeError::NoReference(1)));
+}
+
+#[test]
+fn fast_paths_agree_with_their_references() {
+    assert_eq!(disagrees_with_reference(5, "47|53\n97|47\n97|53\n\n97,47,53\n53,47,97\n"), Some(false));
+    for map in ["2333133121414131402", "12345", "10101", "9", "1"] {
+        assert_eq!(disagrees_with_reference(9, map), Some(false), "{}", map);
+    }
+    assert_eq!(disagrees_with_reference(11, "125 17"), Some(false));
+    assert_eq!(disagrees_with_reference(1, "3   4\n"), None);
+}
+
+/// Minimizing runs concurrently on several threads, each recording its own panics.
+#[test]
+fn minimize_shrinks_a_panicking_input_on_several_threads() {
+    let input = "3   4\n4   3\n2   99999999999\n1   3\n";
+    let minimized = std::thread::scope(|scope| {
+        let workers = (0..4)
+            .map(|_| scope.spawn(|| minimize(1, input, &Predicate::Panics(Part::One)).unwrap()))
+            .collect::<Vec<_>>();
+        workers.into_iter().map(|worker| worker.join().unwrap()).collect::<Vec<String>>()
+    });
+    assert!(minimized.iter().all(|candidate| *candidate == minimized[0]), "{:?}", minimized);
+    assert_eq!(minimized[0], "9999999999", "the fewest digits that overflow an i32");
+    assert!(rust::solve(2024, 1, Part::One, &minimized[0]).is_err());
+}
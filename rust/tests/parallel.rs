@@ -0,0 +1,68 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use rust::{parallel, puzzle, Part};
+
+/// Tests run in parallel but the number of jobs is global, so each one holds this while it sets
+/// and relies on it.
+static JOBS: Mutex<()> = Mutex::new(());
+
+fn jobs_lock() -> MutexGuard<'static, ()> {
+    JOBS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[test]
+fn maps_keep_the_input_order() {
+    let _jobs = jobs_lock();
+    parallel::set_jobs(4);
+    let items = (0..1000).collect::<Vec<u32>>();
+    let squares = items.iter().map(|i| i * i).collect::<Vec<u32>>();
+    assert_eq!(parallel::map(&items, |i| i * i), squares);
+    assert_eq!(parallel::map_each(&items, |i| i * i), squares);
+    assert_eq!(parallel::map_chunks(&items, |chunk| chunk.to_vec()), items);
+    assert!(parallel::map(&[] as &[u32], |i| *i).is_empty());
+}
+
+#[test]
+fn later_calls_reuse_the_pool() {
+    let _jobs = jobs_lock();
+    parallel::set_jobs(4);
+    let items = (0..100).collect::<Vec<u32>>();
+    parallel::map(&items, |i| i + 1);
+    let started = parallel::workers();
+    for _ in 0..10 {
+        parallel::map_each(&items, |i| i + 1);
+    }
+    assert_eq!(parallel::workers(), started);
+}
+
+#[test]
+fn panics_reach_the_caller_and_spare_the_pool() {
+    let _jobs = jobs_lock();
+    parallel::set_jobs(4);
+    let items = (0..8).collect::<Vec<u32>>();
+    let result = std::panic::catch_unwind(|| parallel::map(&items, |i| if *i == 5 { panic!("five") } else { *i }));
+    assert!(result.is_err());
+    assert_eq!(parallel::map(&items, |i| i * 2), [0, 2, 4, 6, 8, 10, 12, 14]);
+}
+
+/// Days whose loops run on several threads give the same answers as on one.
+#[test]
+fn answers_do_not_depend_on_jobs() {
+    let _jobs = jobs_lock();
+    let inputs = [
+        (2, "7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9\n"),
+        (6, "....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^.....\n........#.\n#.........\n......#...\n"),
+        (7, "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n7290: 6 8 6 15\n161011: 16 10 13\n192: 17 8 14\n21037: 9 7 18 13\n292: 11 6 16 20\n"),
+    ];
+    let answers = |jobs| {
+        parallel::set_jobs(jobs);
+        inputs.iter()
+            .flat_map(|(day, input)| {
+                let mut puzzle = puzzle(*day).unwrap();
+                let data = puzzle.parse_input(input);
+                [Part::One, Part::Two].map(|part| puzzle.solve(data.as_ref(), part).to_string())
+            })
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(answers(4), answers(1));
+    assert_eq!(answers(1), ["2", "4", "41", "6", "3749", "11387"]);
+}
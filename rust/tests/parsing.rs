@@ -0,0 +1,67 @@
+use rust::parse;
+use rust::parsing::{blocks, char_grid, digit_grid, ints, scan, ParseError};
+
+#[test]
+fn ints_read_signs_only_before_digits() {
+    assert_eq!(ints::<i32>("p=0,4 v=3,-3"), Ok(vec![0, 4, 3, -3]));
+    assert_eq!(ints::<i32>("a - b -- 7-2"), Ok(vec![7, -2]));
+    assert_eq!(ints::<u8>("no numbers"), Ok(vec![]));
+}
+
+#[test]
+fn ints_report_values_that_do_not_fit() {
+    let error = ints::<u8>("1 300").unwrap_err();
+    assert!(matches!(error, ParseError::Value { index: 1, ref token, .. } if token == "300"), "{:?}", error);
+    assert!(ints::<u32>("-1").is_err());
+}
+
+#[test]
+fn blocks_split_on_blank_lines() {
+    assert_eq!(blocks("a\nb\n\n\n  \nc\n"), vec![vec!["a", "b"], vec!["c"]]);
+    assert_eq!(blocks("\n\n"), Vec::<Vec<&str>>::new());
+}
+
+#[test]
+fn char_grids_skip_empty_lines() {
+    assert_eq!(char_grid("ab\n\ncd\n"), vec![vec!['a', 'b'], vec!['c', 'd']]);
+}
+
+#[test]
+fn digit_grids_reject_other_characters() {
+    assert_eq!(digit_grid("01\n89\n"), Ok(vec![vec![0, 1], vec![8, 9]]));
+    assert!(digit_grid("1.\n").is_err());
+    let error = digit_grid("12\n3x\n").unwrap_err();
+    assert!(matches!(error, ParseError::Value { index: 1, ref token, .. } if token == "x"), "{:?}", error);
+}
+
+#[test]
+fn scan_captures_between_literals() {
+    assert_eq!(scan("p={},{} v={}", "p= 1,2 v=-3"), Ok(vec!["1", "2", "-3"]));
+    assert_eq!(scan("{}: {}", "190: 10 19"), Ok(vec!["190", "10 19"]));
+    assert_eq!(scan("{}", ""), Ok(vec![""]));
+}
+
+#[test]
+fn scan_reports_where_the_input_stops_matching() {
+    let mismatch = |at| ParseError::Mismatch { pattern: "x={},y={}".to_string(), input: "x=1;y=2".to_string(), at };
+    assert_eq!(scan("x={},y={}", "x=1;y=2"), Err(mismatch(2)));
+    assert_eq!(scan("a{}b", "xab").unwrap_err(), ParseError::Mismatch { pattern: "a{}b".to_string(), input: "xab".to_string(), at: 0 });
+    assert_eq!(scan("{}|", "1|2").unwrap_err(), ParseError::Mismatch { pattern: "{}|".to_string(), input: "1|2".to_string(), at: 0 });
+    assert_eq!(scan("{}{}", "12"), Err(ParseError::AmbiguousPattern("{}{}".to_string())));
+}
+
+#[test]
+fn parse_builds_typed_tuples() {
+    let (before, after): (i32, i32) = parse!("{}|{}", "47|53").unwrap();
+    assert_eq!((before, after), (47, 53));
+    let (name, count): (String, u8) = parse!("{} x{}", "stone x3").unwrap();
+    assert_eq!((name.as_str(), count), ("stone", 3));
+}
+
+#[test]
+fn parse_reports_arity_and_bad_values() {
+    let arity: Result<(i32,), ParseError> = parse!("{}|{}", "1|2");
+    assert_eq!(arity, Err(ParseError::Arity { expected: 1, found: 2 }));
+    let value: Result<(i32, i32), ParseError> = parse!("{}|{}", "1|two");
+    assert!(matches!(value, Err(ParseError::Value { index: 1, ref token, .. }) if token == "two"), "{:?}", value);
+}
@@ -0,0 +1,94 @@
+//! The timeout watchdog, on solvers that take their time. They claim to be Day 1 to read its
+//! data files, and ignore what they read.
+
+use std::thread;
+use std::time::{Duration, Instant};
+use rust::runner::{watch, Options, STATS_HEADER};
+use rust::{cancel, erase, DaySolution};
+
+/// Solves part 1 at once, and part 2 only once cancelled, checking every millisecond.
+struct Cooperative;
+
+impl DaySolution<(), u32> for Cooperative {
+    fn new() -> Self {
+        Cooperative
+    }
+
+    fn day(&self) -> u16 {
+        1
+    }
+
+    fn parse_input(&mut self, _raw_data: &str) {}
+
+    fn part1(&mut self, _input: &()) -> u32 {
+        1
+    }
+
+    fn part2(&mut self, _input: &()) -> u32 {
+        while !cancel::cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        0
+    }
+}
+
+/// Never finishes part 1 and never checks for cancellation.
+struct Stubborn;
+
+impl DaySolution<(), u32> for Stubborn {
+    fn new() -> Self {
+        Stubborn
+    }
+
+    fn day(&self) -> u16 {
+        1
+    }
+
+    fn parse_input(&mut self, _raw_data: &str) {}
+
+    fn part1(&mut self, _input: &()) -> u32 {
+        loop {
+            thread::sleep(Duration::from_secs(1));
+        }
+    }
+
+    fn part2(&mut self, _input: &()) -> u32 {
+        2
+    }
+}
+
+const LIMIT: Duration = Duration::from_millis(100);
+
+#[test]
+fn slow_parts_time_out() {
+    let report = watch(erase::<Cooperative, _, _>, &Options::default(), LIMIT);
+    assert!(report.timed_out);
+    let parts = report.text.lines().filter(|line| line.contains(":: Part")).collect::<Vec<&str>>();
+    assert_eq!(parts.len(), 2, "nothing runs after the timeout: {}", report.text);
+    assert!(parts[0].starts_with("Example :: Part 1 ====> ("), "{}", report.text);
+    assert_eq!(parts[1], "Example :: Part 2 ====> TIMEOUT after 0.1s");
+    assert!(report.text.ends_with(&format!("TIMEOUT after 0.1s\n{}\n", "-".repeat(60))), "{}", report.text);
+
+    let columns = STATS_HEADER.split('\t').count();
+    assert!(report.rows.iter().all(|row| row.split('\t').count() == columns), "{:?}", report.rows);
+    let stages = report.rows.iter().map(|row| row.split('\t').nth(2).unwrap()).collect::<Vec<&str>>();
+    assert_eq!(stages, ["parse", "part1", "part2"]);
+    assert_eq!(report.rows[2], "1\texample\tpart2\tdefault\t-\t-\t-\t-\ttimeout");
+    assert_ne!(report.rows[1].rsplit('\t').next(), Some("timeout"));
+}
+
+/// The watchdog leaves a solver that never checks behind and gives the next day its full time.
+#[test]
+fn solvers_that_never_check_are_abandoned() {
+    let start = Instant::now();
+    let report = watch(erase::<Stubborn, _, _>, &Options::default(), LIMIT);
+    assert!(start.elapsed() < LIMIT * 5, "took {:?}", start.elapsed());
+    assert!(report.text.contains("Example :: Part 1 ====> TIMEOUT after 0.1s\n"), "{}", report.text);
+    assert_eq!(report.rows.last().unwrap(), "1\texample\tpart1\tdefault\t-\t-\t-\t-\ttimeout");
+
+    assert!(report.timed_out);
+
+    let next = watch(erase::<Cooperative, _, _>, &Options::default(), LIMIT);
+    assert!(next.text.contains("Example :: Part 1 ====> ("), "{}", next.text);
+    assert!(next.text.contains("Example :: Part 2 ====> TIMEOUT after 0.1s\n"), "{}", next.text);
+}
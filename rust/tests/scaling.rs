@@ -0,0 +1,29 @@
+use rust::generate::Knobs;
+use rust::scaling::{expected_exponent, fit, scale, Sample};
+use rust::Part;
+
+#[test]
+fn fit_finds_the_exponent() {
+    let samples = [10, 20, 40, 80].map(|n| Sample { n, bytes: n, millis: 0.01 * (n as f64).powi(2) });
+    assert!((fit(&samples).unwrap() - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn fit_ignores_noise_level_times() {
+    let samples = [10, 20].map(|n| Sample { n, bytes: n, millis: 0.001 });
+    assert_eq!(fit(&samples), None);
+}
+
+#[test]
+fn scale_grows_cells_or_counts() {
+    let grid = scale(&Knobs::official(6), 4.0);
+    assert_eq!((grid.width, grid.height), (260, 260));
+    let equations = scale(&Knobs::official(7), 4.0);
+    assert_eq!((equations.count, equations.width), (3400, 12));
+}
+
+#[test]
+fn trails_are_expected_to_scale_linearly() {
+    assert_eq!(expected_exponent(10, Part::One), 1.0);
+    assert_eq!(expected_exponent(10, Part::Two), 1.0);
+}